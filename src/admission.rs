@@ -0,0 +1,185 @@
+//! Player admission and join gating.
+//!
+//! These types centralize the decision of whether a server can take on a new
+//! player, so Horizon and Atlas apply the same rules.
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+
+use crate::player::PlayerId;
+use crate::spatial::RegionCoordinate;
+
+/// Reason a player was refused admission.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RejectReason {
+    /// Server has no free player slots
+    AtCapacity,
+    /// Server load is above the allowed maximum
+    Overloaded,
+}
+
+/// Outcome of an admission check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AdmissionDecision {
+    /// Player can join immediately
+    Admit,
+    /// Player cannot join this server
+    Reject(RejectReason),
+    /// Server is close to its load limit; player should wait
+    Queue,
+}
+
+/// Decides whether a server should admit a new player.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AdmissionController {
+    /// Distance below `max_load` at which players are queued instead of admitted
+    pub queue_margin: f32,
+}
+
+impl Default for AdmissionController {
+    fn default() -> Self {
+        Self { queue_margin: 0.05 }
+    }
+}
+
+impl AdmissionController {
+    /// Creates a controller with the given queue margin.
+    pub fn new(queue_margin: f32) -> Self {
+        Self { queue_margin }
+    }
+
+    /// Checks whether a player can be admitted given the server's current state.
+    pub fn can_admit(&self, current: u32, capacity: u32, load: f32, max_load: f32) -> AdmissionDecision {
+        if current >= capacity {
+            AdmissionDecision::Reject(RejectReason::AtCapacity)
+        } else if load > max_load {
+            AdmissionDecision::Reject(RejectReason::Overloaded)
+        } else if load >= max_load - self.queue_margin {
+            AdmissionDecision::Queue
+        } else {
+            AdmissionDecision::Admit
+        }
+    }
+}
+
+/// A player waiting in a [`JoinQueue`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QueuedPlayer {
+    /// Waiting player
+    pub player_id: PlayerId,
+    /// Queue priority (higher = served first)
+    pub priority: u8,
+    /// When the player joined the queue
+    pub enqueued_at: DateTime<Utc>,
+}
+
+/// Waiting room for players trying to join a full region.
+///
+/// Higher priorities are served first; players with equal priority are served
+/// in the order they were enqueued.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JoinQueue {
+    /// Region this queue belongs to
+    pub region: RegionCoordinate,
+    entries: Vec<QueuedPlayer>,
+}
+
+impl JoinQueue {
+    /// Creates an empty queue for a region.
+    pub fn new(region: RegionCoordinate) -> Self {
+        Self {
+            region,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Adds a player to the queue and returns their position.
+    ///
+    /// A player already in the queue keeps their existing place.
+    pub fn enqueue(&mut self, player_id: PlayerId, priority: u8, now: DateTime<Utc>) -> usize {
+        if let Some(position) = self.position(&player_id) {
+            return position;
+        }
+        let index = self
+            .entries
+            .iter()
+            .position(|e| e.priority < priority || (e.priority == priority && e.enqueued_at > now))
+            .unwrap_or(self.entries.len());
+        self.entries.insert(index, QueuedPlayer { player_id, priority, enqueued_at: now });
+        index
+    }
+
+    /// Removes and returns the next player to admit.
+    pub fn dequeue_next(&mut self) -> Option<QueuedPlayer> {
+        if self.entries.is_empty() {
+            None
+        } else {
+            Some(self.entries.remove(0))
+        }
+    }
+
+    /// Gets a player's zero-based position in the queue.
+    pub fn position(&self, player_id: &PlayerId) -> Option<usize> {
+        self.entries.iter().position(|e| &e.player_id == player_id)
+    }
+
+    /// Removes a player who gave up waiting. Returns false if not queued.
+    pub fn remove(&mut self, player_id: &PlayerId) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|e| &e.player_id != player_id);
+        self.entries.len() != before
+    }
+
+    /// Number of waiting players.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if nobody is waiting.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_admission_decisions() {
+        let controller = AdmissionController::default();
+        assert_eq!(controller.can_admit(10, 100, 0.1, 0.9), AdmissionDecision::Admit);
+        assert_eq!(
+            controller.can_admit(100, 100, 0.5, 0.9),
+            AdmissionDecision::Reject(RejectReason::AtCapacity)
+        );
+        assert_eq!(
+            controller.can_admit(10, 100, 0.95, 0.9),
+            AdmissionDecision::Reject(RejectReason::Overloaded)
+        );
+        assert_eq!(controller.can_admit(10, 100, 0.87, 0.9), AdmissionDecision::Queue);
+    }
+
+    #[test]
+    fn test_join_queue_priority_and_fifo() {
+        let mut queue = JoinQueue::new(RegionCoordinate::center());
+        let now = Utc::now();
+        queue.enqueue(PlayerId::from("a"), 0, now);
+        queue.enqueue(PlayerId::from("b"), 0, now + chrono::Duration::seconds(1));
+        queue.enqueue(PlayerId::from("vip"), 5, now + chrono::Duration::seconds(2));
+        queue.enqueue(PlayerId::from("c"), 0, now + chrono::Duration::seconds(3));
+
+        assert_eq!(queue.position(&PlayerId::from("vip")), Some(0));
+        assert_eq!(queue.position(&PlayerId::from("b")), Some(2));
+        assert_eq!(queue.position(&PlayerId::from("missing")), None);
+        assert_eq!(queue.enqueue(PlayerId::from("a"), 9, now), 1);
+
+        let order: Vec<String> = std::iter::from_fn(|| queue.dequeue_next())
+            .map(|p| p.player_id.0)
+            .collect();
+        assert_eq!(order, vec!["vip", "a", "b", "c"]);
+        assert!(queue.is_empty());
+    }
+}
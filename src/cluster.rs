@@ -0,0 +1,217 @@
+//! Cluster-wide bookkeeping helpers used by Atlas.
+//!
+//! These helpers operate on Atlas's view of the whole cluster (all registered
+//! servers and tracked players) rather than on a single server.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::player::{PlayerId, PlayerInfo};
+use crate::server::{ServerId, ServerInfo};
+use crate::spatial::{RegionCoordinate, WorldCoordinate};
+
+/// Inconsistency found by [`check_consistency`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "issue")]
+pub enum ConsistencyIssue {
+    /// Player's current server is not registered
+    UnknownServer {
+        player_id: PlayerId,
+        server_id: ServerId,
+    },
+    /// Player's last position is outside its server's bounds
+    PlayerOutOfBounds {
+        player_id: PlayerId,
+        server_id: ServerId,
+        position: WorldCoordinate,
+    },
+    /// More than one server claims the same region
+    DuplicateRegionOwnership {
+        region: RegionCoordinate,
+        servers: Vec<ServerId>,
+    },
+}
+
+/// Error returned when a region already has a different owner.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("Region ({}, {}, {}) is already assigned to {owner}", region.x, region.y, region.z)]
+pub struct AlreadyAssigned {
+    /// Region that was requested
+    pub region: RegionCoordinate,
+    /// Server currently owning the region
+    pub owner: ServerId,
+}
+
+/// Authoritative region to server assignments.
+///
+/// Enforces the one-server-per-region rule: a region can only be assigned to
+/// a new server after its current owner releases it.
+#[derive(Debug, Clone, Default)]
+pub struct AssignmentLedger {
+    owners: HashMap<RegionCoordinate, ServerId>,
+}
+
+impl AssignmentLedger {
+    /// Creates an empty ledger.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Assigns a region to a server.
+    ///
+    /// Re-assigning a region to its current owner succeeds and changes nothing.
+    pub fn assign(&mut self, region: RegionCoordinate, server_id: ServerId) -> Result<(), AlreadyAssigned> {
+        match self.owners.get(&region) {
+            Some(owner) if *owner != server_id => Err(AlreadyAssigned {
+                region,
+                owner: owner.clone(),
+            }),
+            Some(_) => Ok(()),
+            None => {
+                self.owners.insert(region, server_id);
+                Ok(())
+            }
+        }
+    }
+
+    /// Releases a region, returning the server that owned it.
+    pub fn release(&mut self, region: &RegionCoordinate) -> Option<ServerId> {
+        self.owners.remove(region)
+    }
+
+    /// Gets the server owning a region.
+    pub fn owner(&self, region: &RegionCoordinate) -> Option<&ServerId> {
+        self.owners.get(region)
+    }
+
+    /// Number of assigned regions.
+    pub fn len(&self) -> usize {
+        self.owners.len()
+    }
+
+    /// Returns true if no regions are assigned.
+    pub fn is_empty(&self) -> bool {
+        self.owners.is_empty()
+    }
+}
+
+/// Cross-checks the server registry against the player index.
+///
+/// Returns every issue found; an empty result means the views agree.
+pub fn check_consistency(servers: &[ServerInfo], players: &[PlayerInfo]) -> Vec<ConsistencyIssue> {
+    let mut issues = Vec::new();
+
+    let mut owners: HashMap<RegionCoordinate, Vec<ServerId>> = HashMap::new();
+    for server in servers {
+        owners.entry(server.region_coord).or_default().push(server.id.clone());
+    }
+    for server in servers {
+        if let Some(ids) = owners.remove(&server.region_coord) {
+            if ids.len() > 1 {
+                issues.push(ConsistencyIssue::DuplicateRegionOwnership {
+                    region: server.region_coord,
+                    servers: ids,
+                });
+            }
+        }
+    }
+
+    let by_id: HashMap<&ServerId, &ServerInfo> = servers.iter().map(|s| (&s.id, s)).collect();
+    for player in players {
+        let Some(server_id) = &player.current_server else {
+            continue;
+        };
+        match by_id.get(server_id) {
+            None => issues.push(ConsistencyIssue::UnknownServer {
+                player_id: player.id.clone(),
+                server_id: server_id.clone(),
+            }),
+            Some(server) if !server.bounds.contains(&player.last_position) => {
+                issues.push(ConsistencyIssue::PlayerOutOfBounds {
+                    player_id: player.id.clone(),
+                    server_id: server_id.clone(),
+                    position: player.last_position,
+                })
+            }
+            Some(_) => {}
+        }
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spatial::RegionBounds;
+
+    fn server(id: &str, region: RegionCoordinate) -> ServerInfo {
+        let mut info = ServerInfo::new(
+            id.to_string(),
+            "127.0.0.1:8080".to_string(),
+            region,
+            RegionBounds::from_center(region.to_world_center(100.0), 50.0),
+            100,
+        );
+        info.id = ServerId::from(id);
+        info
+    }
+
+    fn player(id: &str, server: &str, position: WorldCoordinate) -> PlayerInfo {
+        let mut info = PlayerInfo::new(PlayerId::from(id), id.to_string());
+        info.current_server = Some(ServerId::from(server));
+        info.last_position = position;
+        info
+    }
+
+    #[test]
+    fn test_assignment_ledger() {
+        let region = RegionCoordinate::new(1, 0, 0);
+        let mut ledger = AssignmentLedger::new();
+        assert!(ledger.assign(region, ServerId::from("a")).is_ok());
+        assert!(ledger.assign(region, ServerId::from("a")).is_ok());
+        assert_eq!(ledger.owner(&region), Some(&ServerId::from("a")));
+
+        let err = ledger.assign(region, ServerId::from("b")).unwrap_err();
+        assert_eq!(err.owner, ServerId::from("a"));
+        assert_eq!(err.to_string(), "Region (1, 0, 0) is already assigned to a");
+
+        assert_eq!(ledger.release(&region), Some(ServerId::from("a")));
+        assert!(ledger.assign(region, ServerId::from("b")).is_ok());
+        assert_eq!(ledger.len(), 1);
+    }
+
+    #[test]
+    fn test_consistent_cluster() {
+        let servers = vec![server("a", RegionCoordinate::new(0, 0, 0))];
+        let players = vec![player("p1", "a", WorldCoordinate::new(10.0, 0.0, 0.0))];
+        assert!(check_consistency(&servers, &players).is_empty());
+    }
+
+    #[test]
+    fn test_consistency_issues() {
+        let servers = vec![
+            server("a", RegionCoordinate::new(0, 0, 0)),
+            server("b", RegionCoordinate::new(1, 0, 0)),
+            server("c", RegionCoordinate::new(1, 0, 0)),
+        ];
+        let players = vec![
+            player("p1", "missing", WorldCoordinate::zero()),
+            player("p2", "a", WorldCoordinate::new(500.0, 0.0, 0.0)),
+        ];
+        let issues = check_consistency(&servers, &players);
+        assert_eq!(issues.len(), 3);
+        assert!(issues.contains(&ConsistencyIssue::DuplicateRegionOwnership {
+            region: RegionCoordinate::new(1, 0, 0),
+            servers: vec![ServerId::from("b"), ServerId::from("c")],
+        }));
+        assert!(issues.contains(&ConsistencyIssue::UnknownServer {
+            player_id: PlayerId::from("p1"),
+            server_id: ServerId::from("missing"),
+        }));
+        assert!(issues.iter().any(|issue| matches!(
+            issue,
+            ConsistencyIssue::PlayerOutOfBounds { player_id, .. } if player_id.as_str() == "p2"
+        )));
+    }
+}
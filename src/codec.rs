@@ -0,0 +1,253 @@
+//! Encoding helpers shared by the transfer and messaging paths.
+
+use serde::de::DeserializeOwned;
+
+/// Errors from size-limited deserialization.
+#[derive(Debug, thiserror::Error)]
+pub enum DecodeError {
+    /// Input is larger than the allowed maximum
+    #[error("Payload of {size} bytes exceeds limit of {max} bytes")]
+    TooLarge { size: usize, max: usize },
+
+    /// Input is not valid JSON for the target type
+    #[error("Invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// Input starts with an unknown format flag
+    #[error("Unknown payload format flag: {0}")]
+    UnknownFormat(u8),
+
+    /// Compressed input could not be decompressed
+    #[error("Decompression failed: {0}")]
+    Decompress(#[from] std::io::Error),
+
+    /// Compressed input inflates to more than the allowed maximum
+    #[error("Decompressed payload exceeds limit of {max} bytes")]
+    DecompressedTooLarge { max: usize },
+}
+
+/// Error for one element of a batch decoded by [`from_json_lenient_vec`].
+#[derive(Debug, thiserror::Error)]
+#[error("Invalid element {}: {source}", index.map_or_else(|| "array".to_string(), |i| i.to_string()))]
+pub struct DeserializeError {
+    /// Position of the element in the array, or None if the input was not a JSON array
+    pub index: Option<usize>,
+    /// Underlying parse error
+    pub source: serde_json::Error,
+}
+
+/// Deserializes a JSON array element by element, keeping the valid ones.
+///
+/// One malformed heartbeat or position in a batch does not discard the rest:
+/// successes and per-element errors are returned separately, so the caller can
+/// process the good entries and log the bad ones. If the input is not a JSON
+/// array at all, the result is a single error with no index.
+pub fn from_json_lenient_vec<T: DeserializeOwned>(json: &str) -> (Vec<T>, Vec<DeserializeError>) {
+    let elements: Vec<serde_json::Value> = match serde_json::from_str(json) {
+        Ok(elements) => elements,
+        Err(source) => return (Vec::new(), vec![DeserializeError { index: None, source }]),
+    };
+
+    let mut values = Vec::with_capacity(elements.len());
+    let mut errors = Vec::new();
+    for (index, element) in elements.into_iter().enumerate() {
+        match serde_json::from_value(element) {
+            Ok(value) => values.push(value),
+            Err(source) => errors.push(DeserializeError { index: Some(index), source }),
+        }
+    }
+    (values, errors)
+}
+
+/// Deserializes JSON, rejecting input longer than `max_bytes` before parsing.
+///
+/// Use this instead of `serde_json::from_str` for anything received from a peer,
+/// so an oversized payload is refused without allocating for its contents.
+pub fn from_json_limited<T: DeserializeOwned>(json: &str, max_bytes: usize) -> Result<T, DecodeError> {
+    if json.len() > max_bytes {
+        return Err(DecodeError::TooLarge { size: json.len(), max: max_bytes });
+    }
+    Ok(serde_json::from_str(json)?)
+}
+
+/// Byte-slice counterpart of [`from_json_limited`].
+pub fn from_json_slice_limited<T: DeserializeOwned>(json: &[u8], max_bytes: usize) -> Result<T, DecodeError> {
+    if json.len() > max_bytes {
+        return Err(DecodeError::TooLarge { size: json.len(), max: max_bytes });
+    }
+    Ok(serde_json::from_slice(json)?)
+}
+
+/// Computes a stable 64-bit FNV-1a checksum.
+///
+/// Unlike `DefaultHasher`, the result is identical across Rust versions and
+/// platforms, so it can be compared between services.
+pub fn checksum(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    bytes.iter().fold(OFFSET_BASIS, |hash, &byte| (hash ^ byte as u64).wrapping_mul(PRIME))
+}
+
+/// Compresses bytes with DEFLATE.
+#[cfg(feature = "compression")]
+pub fn compress(bytes: &[u8]) -> std::io::Result<Vec<u8>> {
+    use std::io::Write;
+
+    let mut encoder = flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(bytes)?;
+    encoder.finish()
+}
+
+/// Decompresses DEFLATE-compressed bytes, refusing output longer than `max_len`.
+///
+/// Inflation stops one byte past the limit, so a small malicious payload
+/// cannot expand into gigabytes before it is rejected.
+#[cfg(feature = "compression")]
+pub fn decompress(bytes: &[u8], max_len: usize) -> Result<Vec<u8>, DecodeError> {
+    use std::io::Read;
+
+    let mut decoded = Vec::new();
+    flate2::read::DeflateDecoder::new(bytes)
+        .take((max_len as u64).saturating_add(1))
+        .read_to_end(&mut decoded)?;
+    if decoded.len() > max_len {
+        return Err(DecodeError::DecompressedTooLarge { max: max_len });
+    }
+    Ok(decoded)
+}
+
+/// Serde `with` module encoding `Vec<u8>` as base64 in human-readable formats.
+///
+/// The default encoding is a JSON array of numbers, three to four times the
+/// size of the bytes. Binary formats get the bytes as-is. Use with
+/// `#[serde(with = "base64_bytes")]`.
+pub mod base64_bytes {
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+    use serde::de::{self, SeqAccess, Visitor};
+    use serde::{Deserializer, Serializer};
+    use std::fmt;
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&STANDARD.encode(bytes))
+        } else {
+            serializer.serialize_bytes(bytes)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        struct BytesVisitor;
+
+        impl<'de> Visitor<'de> for BytesVisitor {
+            type Value = Vec<u8>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("base64 string or bytes")
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Vec<u8>, E> {
+                STANDARD.decode(v).map_err(E::custom)
+            }
+
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Vec<u8>, E> {
+                Ok(v.to_vec())
+            }
+
+            fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Vec<u8>, E> {
+                Ok(v)
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<u8>, A::Error> {
+                let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+                while let Some(byte) = seq.next_element()? {
+                    bytes.push(byte);
+                }
+                Ok(bytes)
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(BytesVisitor)
+        } else {
+            deserializer.deserialize_byte_buf(BytesVisitor)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn test_decompress_limit() {
+        let bomb = compress(&vec![0u8; 1 << 20]).unwrap();
+        assert!(bomb.len() < 4096);
+        assert!(matches!(
+            decompress(&bomb, 64 * 1024),
+            Err(DecodeError::DecompressedTooLarge { max: 65536 })
+        ));
+        assert_eq!(decompress(&bomb, 1 << 20).unwrap().len(), 1 << 20);
+    }
+
+    #[test]
+    fn test_base64_bytes() {
+        #[derive(serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+        struct Blob {
+            #[serde(with = "base64_bytes")]
+            data: Vec<u8>,
+        }
+
+        let blob = Blob { data: vec![0, 1, 2, 250, 255] };
+        let json = serde_json::to_string(&blob).unwrap();
+        assert_eq!(json, r#"{"data":"AAEC+v8="}"#);
+        assert_eq!(serde_json::from_str::<Blob>(&json).unwrap(), blob);
+        assert!(serde_json::from_str::<Blob>(r#"{"data":"not base64!"}"#).is_err());
+    }
+
+    #[test]
+    fn test_checksum_is_stable() {
+        assert_eq!(checksum(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(checksum(b"horizon"), checksum(b"horizon"));
+        assert_ne!(checksum(b"horizon"), checksum(b"horizoN"));
+    }
+
+    #[test]
+    fn test_from_json_lenient_vec() {
+        use crate::spatial::WorldCoordinate;
+
+        let json = r#"[
+            {"x": 1.0, "y": 2.0, "z": 3.0},
+            {"x": "oops", "y": 0.0, "z": 0.0},
+            {"x": 4.0, "y": 5.0, "z": 6.0},
+            42
+        ]"#;
+        let (coords, errors) = from_json_lenient_vec::<WorldCoordinate>(json);
+        assert_eq!(coords, [WorldCoordinate::new(1.0, 2.0, 3.0), WorldCoordinate::new(4.0, 5.0, 6.0)]);
+        let indices: Vec<Option<usize>> = errors.iter().map(|e| e.index).collect();
+        assert_eq!(indices, [Some(1), Some(3)]);
+
+        let (coords, errors) = from_json_lenient_vec::<WorldCoordinate>("{\"x\": 1.0}");
+        assert!(coords.is_empty());
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].index, None);
+    }
+
+    #[test]
+    fn test_from_json_limited() {
+        let values: Vec<u32> = from_json_limited("[1, 2, 3]", 16).unwrap();
+        assert_eq!(values, vec![1, 2, 3]);
+
+        let oversized = format!("[{}]", vec!["0"; 1000].join(","));
+        match from_json_limited::<Vec<u32>>(&oversized, 1024) {
+            Err(DecodeError::TooLarge { size, max }) => {
+                assert_eq!(size, oversized.len());
+                assert_eq!(max, 1024);
+            }
+            other => panic!("Expected TooLarge, got {:?}", other),
+        }
+        assert!(matches!(from_json_limited::<Vec<u32>>("[1,", 16), Err(DecodeError::Json(_))));
+    }
+}
@@ -0,0 +1,60 @@
+//! Shared error code catalog.
+//!
+//! Stable, machine-readable codes that any service can attach to an error so
+//! clients and analytics can categorize failures without parsing messages.
+
+use serde::{Deserialize, Serialize};
+
+/// Broad category of an [`ErrorCode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    /// Caused by the client or peer (bad input, auth, rate limits)
+    Client,
+    /// Caused by the server (capacity, internal failures)
+    Server,
+    /// Caused by the network between the two
+    Network,
+}
+
+/// Machine-readable error code shared across the Horizon ecosystem.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// Message could not be parsed or failed validation
+    InvalidMessage,
+    /// Peer speaks an incompatible protocol version
+    ProtocolVersionMismatch,
+    /// Authentication failed or credentials expired
+    AuthenticationFailed,
+    /// Peer exceeded its rate limit
+    RateLimited,
+    /// Server has no free capacity
+    ServerFull,
+    /// Server is overloaded
+    ServerOverloaded,
+    /// Player transfer failed
+    TransferFailed,
+    /// Unexpected internal server error
+    InternalError,
+    /// Connection was lost
+    ConnectionLost,
+    /// Operation timed out
+    Timeout,
+}
+
+impl ErrorCode {
+    /// Gets the category this code belongs to.
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            Self::InvalidMessage
+            | Self::ProtocolVersionMismatch
+            | Self::AuthenticationFailed
+            | Self::RateLimited => ErrorCategory::Client,
+            Self::ServerFull | Self::ServerOverloaded | Self::TransferFailed | Self::InternalError => {
+                ErrorCategory::Server
+            }
+            Self::ConnectionLost | Self::Timeout => ErrorCategory::Network,
+        }
+    }
+}
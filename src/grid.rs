@@ -0,0 +1,640 @@
+//! Data structures over the region grid.
+//!
+//! `RegionDims` and `RegionOccupancy` are dense and complement the sparse
+//! `HashMap`-based lookups used elsewhere when Atlas repeatedly queries a
+//! bounded world. `RegionGrid` is the sparse region to server map, and
+//! `DistanceMatrix` caches distances between a fixed set of regions.
+//! `WorldSnapshot` is an exportable record of the partition for offline
+//! analysis.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::server::{ServerId, ServerInfo};
+use crate::spatial::{RegionBounds, RegionCoordinate};
+
+/// Largest number of regions a [`RegionOccupancy`] may cover (128 MiB of bits).
+pub const MAX_OCCUPANCY_REGIONS: usize = 1 << 30;
+
+/// Error for region dims or occupancy data that cannot be used.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum InvalidRegionDims {
+    /// `min` exceeds `max` on some axis
+    #[error("Region dims are inverted: min ({}, {}, {}) exceeds max ({}, {}, {})", min.x, min.y, min.z, max.x, max.y, max.z)]
+    Inverted { min: RegionCoordinate, max: RegionCoordinate },
+
+    /// Dims cover more regions than an occupancy map may hold
+    #[error("Region dims cover more than {} regions", MAX_OCCUPANCY_REGIONS)]
+    TooLarge,
+
+    /// Occupancy bitmap length does not match its dims
+    #[error("Occupancy bitmap has {actual} words, expected {expected}")]
+    BitsLength { expected: usize, actual: usize },
+}
+
+/// Inclusive block of region coordinates from `min` to `max`.
+///
+/// Deserialization rejects dims where `min` exceeds `max` on any axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "RawRegionDims")]
+pub struct RegionDims {
+    /// Lowest corner (inclusive)
+    pub min: RegionCoordinate,
+    /// Highest corner (inclusive)
+    pub max: RegionCoordinate,
+}
+
+impl RegionDims {
+    /// Creates dims spanning the two corners, in any order.
+    pub fn new(a: RegionCoordinate, b: RegionCoordinate) -> Self {
+        Self {
+            min: RegionCoordinate::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z)),
+            max: RegionCoordinate::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z)),
+        }
+    }
+
+    /// Checks that `min` does not exceed `max` on any axis.
+    pub fn validate(&self) -> Result<(), InvalidRegionDims> {
+        if self.min.x > self.max.x || self.min.y > self.max.y || self.min.z > self.max.z {
+            return Err(InvalidRegionDims::Inverted { min: self.min, max: self.max });
+        }
+        Ok(())
+    }
+
+    /// Number of regions along each axis (zero for an inverted axis, saturating at `usize::MAX`).
+    pub fn size(&self) -> (usize, usize, usize) {
+        let axis = |min: i64, max: i64| usize::try_from((max as i128 - min as i128 + 1).max(0)).unwrap_or(usize::MAX);
+        (
+            axis(self.min.x, self.max.x),
+            axis(self.min.y, self.max.y),
+            axis(self.min.z, self.max.z),
+        )
+    }
+
+    /// Total number of regions, or None if it does not fit in a `usize`.
+    pub fn checked_volume(&self) -> Option<usize> {
+        let (x, y, z) = self.size();
+        x.checked_mul(y)?.checked_mul(z)
+    }
+
+    /// Total number of regions, saturating at `usize::MAX`.
+    pub fn volume(&self) -> usize {
+        self.checked_volume().unwrap_or(usize::MAX)
+    }
+
+    /// Returns true if the region lies within these dims.
+    pub fn contains(&self, region: &RegionCoordinate) -> bool {
+        (self.min.x..=self.max.x).contains(&region.x)
+            && (self.min.y..=self.max.y).contains(&region.y)
+            && (self.min.z..=self.max.z).contains(&region.z)
+    }
+
+    /// Dense index of a region (x varies fastest), or None if outside.
+    pub fn index_of(&self, region: &RegionCoordinate) -> Option<usize> {
+        if !self.contains(region) {
+            return None;
+        }
+        let (sx, sy, _) = self.size();
+        let x = (region.x as i128 - self.min.x as i128) as usize;
+        let y = (region.y as i128 - self.min.y as i128) as usize;
+        let z = (region.z as i128 - self.min.z as i128) as usize;
+        z.checked_mul(sy)?.checked_add(y)?.checked_mul(sx)?.checked_add(x)
+    }
+
+    /// Region at a dense index. The index must be below `volume()`.
+    pub fn coord_at(&self, index: usize) -> RegionCoordinate {
+        let (sx, sy, _) = self.size();
+        RegionCoordinate::new(
+            self.min.x + (index % sx) as i64,
+            self.min.y + ((index / sx) % sy) as i64,
+            self.min.z + (index / (sx * sy)) as i64,
+        )
+    }
+}
+
+#[derive(Deserialize)]
+struct RawRegionDims {
+    min: RegionCoordinate,
+    max: RegionCoordinate,
+}
+
+impl TryFrom<RawRegionDims> for RegionDims {
+    type Error = InvalidRegionDims;
+
+    fn try_from(raw: RawRegionDims) -> Result<Self, Self::Error> {
+        let dims = RegionDims { min: raw.min, max: raw.max };
+        dims.validate()?;
+        Ok(dims)
+    }
+}
+
+/// Bitmap of which regions in a bounded world have a server.
+///
+/// Deserialization checks the dims and that the bitmap matches their volume.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "RawRegionOccupancy")]
+pub struct RegionOccupancy {
+    dims: RegionDims,
+    bits: Vec<u64>,
+}
+
+impl RegionOccupancy {
+    /// Creates an occupancy map with every region uncovered.
+    ///
+    /// Fails if the dims are inverted or cover more than [`MAX_OCCUPANCY_REGIONS`].
+    pub fn new(dims: RegionDims) -> Result<Self, InvalidRegionDims> {
+        Ok(Self {
+            dims,
+            bits: vec![0; Self::word_count(&dims)?],
+        })
+    }
+
+    fn word_count(dims: &RegionDims) -> Result<usize, InvalidRegionDims> {
+        dims.validate()?;
+        match dims.checked_volume() {
+            Some(volume) if volume <= MAX_OCCUPANCY_REGIONS => Ok(volume.div_ceil(64)),
+            _ => Err(InvalidRegionDims::TooLarge),
+        }
+    }
+
+    /// Dims covered by this map.
+    pub fn dims(&self) -> &RegionDims {
+        &self.dims
+    }
+
+    /// Marks a region as occupied or free. Returns false if outside the dims.
+    pub fn set(&mut self, region: &RegionCoordinate, occupied: bool) -> bool {
+        let Some(index) = self.dims.index_of(region) else {
+            return false;
+        };
+        let mask = 1u64 << (index % 64);
+        if occupied {
+            self.bits[index / 64] |= mask;
+        } else {
+            self.bits[index / 64] &= !mask;
+        }
+        true
+    }
+
+    /// Returns true if the region has a server. Regions outside the dims are never occupied.
+    pub fn is_occupied(&self, region: &RegionCoordinate) -> bool {
+        self.dims
+            .index_of(region)
+            .is_some_and(|index| self.bit(index))
+    }
+
+    /// Number of occupied regions.
+    pub fn occupied_count(&self) -> usize {
+        self.bits.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// Finds the occupied region closest to `from`, searching outward shell by shell.
+    ///
+    /// Distance is Chebyshev; ties are broken by dense index. `from` may lie
+    /// outside the dims. The search starts at the distance from `from` to the
+    /// dims, since nothing occupied can be closer, and stops at the farthest
+    /// region of the dims. Once the shells visited so far would cost more
+    /// than scanning every region it falls back to a linear scan of the
+    /// occupied regions. Returns None if no region is occupied.
+    pub fn nearest_occupied(&self, from: &RegionCoordinate) -> Option<RegionCoordinate> {
+        if self.occupied_count() == 0 {
+            return None;
+        }
+        let clamped = RegionCoordinate::new(
+            from.x.clamp(self.dims.min.x, self.dims.max.x),
+            from.y.clamp(self.dims.min.y, self.dims.max.y),
+            from.z.clamp(self.dims.min.z, self.dims.max.z),
+        );
+        let (min, max) = (self.dims.min, self.dims.max);
+        let farthest = [
+            from.x.abs_diff(min.x).max(from.x.abs_diff(max.x)),
+            from.y.abs_diff(min.y).max(from.y.abs_diff(max.y)),
+            from.z.abs_diff(min.z).max(from.z.abs_diff(max.z)),
+        ]
+        .into_iter()
+        .max()
+        .unwrap_or(0);
+        let volume = self.dims.volume() as u128;
+        let mut work: u128 = 0;
+        let mut radius = from.chebyshev_distance(&clamped);
+        while radius as u64 <= farthest {
+            // A shell of radius r has (2r+1)^3 - (2r-1)^3 = 24r^2 + 2 regions.
+            work += if radius == 0 { 1 } else { 24 * (radius as u128).pow(2) + 2 };
+            if work > volume {
+                break;
+            }
+            let nearest = from
+                .shell(radius)
+                .into_iter()
+                .filter_map(|region| self.dims.index_of(&region))
+                .filter(|&index| self.bit(index))
+                .min();
+            if let Some(index) = nearest {
+                return Some(self.dims.coord_at(index));
+            }
+            radius += 1;
+        }
+        self.occupied_indices()
+            .min_by_key(|&index| (from.chebyshev_distance(&self.dims.coord_at(index)), index))
+            .map(|index| self.dims.coord_at(index))
+    }
+
+    /// Dense indices of occupied regions, in ascending order.
+    fn occupied_indices(&self) -> impl Iterator<Item = usize> + '_ {
+        self.bits.iter().enumerate().flat_map(|(word_index, &word)| {
+            let mut word = word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(word_index * 64 + bit)
+            })
+        })
+    }
+
+    fn bit(&self, index: usize) -> bool {
+        self.bits[index / 64] & (1u64 << (index % 64)) != 0
+    }
+
+    /// Lists every region in the dims without a server.
+    pub fn uncovered_regions(&self) -> Vec<RegionCoordinate> {
+        (0..self.dims.volume())
+            .filter(|&index| !self.bit(index))
+            .map(|index| self.dims.coord_at(index))
+            .collect()
+    }
+}
+
+#[derive(Deserialize)]
+struct RawRegionOccupancy {
+    dims: RegionDims,
+    bits: Vec<u64>,
+}
+
+impl TryFrom<RawRegionOccupancy> for RegionOccupancy {
+    type Error = InvalidRegionDims;
+
+    fn try_from(raw: RawRegionOccupancy) -> Result<Self, Self::Error> {
+        let expected = Self::word_count(&raw.dims)?;
+        if raw.bits.len() != expected {
+            return Err(InvalidRegionDims::BitsLength { expected, actual: raw.bits.len() });
+        }
+        Ok(Self { dims: raw.dims, bits: raw.bits })
+    }
+}
+
+/// Sparse map of which server owns each region.
+///
+/// Unlike [`RegionOccupancy`] this is unbounded and records the owning server,
+/// which makes it suitable for routing and fan-out lookups.
+#[derive(Debug, Clone, Default)]
+pub struct RegionGrid {
+    servers: HashMap<RegionCoordinate, ServerId>,
+}
+
+impl RegionGrid {
+    /// Creates an empty grid.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a grid from registered servers.
+    ///
+    /// If several servers claim a region the last one wins; use
+    /// `check_consistency` to detect that case.
+    pub fn from_servers(servers: &[ServerInfo]) -> Self {
+        Self {
+            servers: servers.iter().map(|s| (s.region_coord, s.id.clone())).collect(),
+        }
+    }
+
+    /// Sets the server owning a region, returning the previous owner.
+    pub fn insert(&mut self, region: RegionCoordinate, server_id: ServerId) -> Option<ServerId> {
+        self.servers.insert(region, server_id)
+    }
+
+    /// Removes a region, returning its owner.
+    pub fn remove(&mut self, region: &RegionCoordinate) -> Option<ServerId> {
+        self.servers.remove(region)
+    }
+
+    /// Gets the server owning a region.
+    pub fn server_at(&self, region: &RegionCoordinate) -> Option<&ServerId> {
+        self.servers.get(region)
+    }
+
+    /// Number of regions with a server.
+    pub fn len(&self) -> usize {
+        self.servers.len()
+    }
+
+    /// Returns true if no region has a server.
+    pub fn is_empty(&self) -> bool {
+        self.servers.is_empty()
+    }
+}
+
+/// One server's entry in a [`WorldSnapshot`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServerSnapshot {
+    /// Server ID
+    pub server_id: ServerId,
+    /// Region the server owns
+    pub region: RegionCoordinate,
+    /// World-space bounds of the region
+    pub bounds: RegionBounds,
+    /// Players on the server when the snapshot was taken
+    pub player_count: u32,
+    /// Player capacity of the server
+    pub capacity: u32,
+}
+
+/// Point-in-time export of the world partition for offline analysis.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorldSnapshot {
+    /// When the snapshot was taken
+    pub captured_at: DateTime<Utc>,
+    /// Every server in the cluster
+    pub servers: Vec<ServerSnapshot>,
+}
+
+impl WorldSnapshot {
+    /// Creates an empty snapshot.
+    pub fn new(captured_at: DateTime<Utc>) -> Self {
+        Self {
+            captured_at,
+            servers: Vec::new(),
+        }
+    }
+
+    /// Records a server and its current player count.
+    pub fn add_server(&mut self, info: &ServerInfo, player_count: u32) {
+        self.servers.push(ServerSnapshot {
+            server_id: info.id.clone(),
+            region: info.region_coord,
+            bounds: info.bounds,
+            player_count,
+            capacity: info.capacity,
+        });
+    }
+
+    /// Total players across all servers.
+    pub fn total_players(&self) -> u64 {
+        self.servers.iter().map(|s| s.player_count as u64).sum()
+    }
+
+    /// Lists the regions within `dims` that no server in the snapshot owns.
+    pub fn coverage_gaps(&self, dims: &RegionDims) -> Result<Vec<RegionCoordinate>, InvalidRegionDims> {
+        let mut occupancy = RegionOccupancy::new(*dims)?;
+        for server in &self.servers {
+            occupancy.set(&server.region, true);
+        }
+        Ok(occupancy.uncovered_regions())
+    }
+
+    /// Serializes the snapshot to JSON.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    /// Deserializes a snapshot from JSON.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+/// Gets the servers within `hops` regions of `center`, nearest first.
+///
+/// Distance is Chebyshev, so diagonal neighbours count as one hop. A server
+/// owning several regions in range is listed once.
+pub fn servers_within_hops(grid: &RegionGrid, center: &RegionCoordinate, hops: i64) -> Vec<ServerId> {
+    let mut servers: Vec<ServerId> = Vec::new();
+    for region in center.spiral(hops) {
+        if let Some(id) = grid.server_at(&region) {
+            if !servers.contains(id) {
+                servers.push(id.clone());
+            }
+        }
+    }
+    servers
+}
+
+/// Precomputed pairwise distances between a fixed set of regions.
+///
+/// Only the upper triangle is stored, so memory grows with `n * (n - 1) / 2`.
+/// Indices refer to positions in the slice the matrix was built from and
+/// panic when out of range, like slice indexing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DistanceMatrix {
+    regions: Vec<RegionCoordinate>,
+    /// (Manhattan, Chebyshev) distance per pair with `i < j`
+    pairs: Vec<(i64, i64)>,
+}
+
+impl DistanceMatrix {
+    /// Computes the distances between every pair of regions.
+    pub fn new(regions: &[RegionCoordinate]) -> Self {
+        let mut pairs = Vec::with_capacity(regions.len() * regions.len().saturating_sub(1) / 2);
+        for (i, a) in regions.iter().enumerate() {
+            for b in &regions[i + 1..] {
+                pairs.push((a.manhattan_distance(b), a.chebyshev_distance(b)));
+            }
+        }
+        Self {
+            regions: regions.to_vec(),
+            pairs,
+        }
+    }
+
+    /// Regions the matrix was built from.
+    pub fn regions(&self) -> &[RegionCoordinate] {
+        &self.regions
+    }
+
+    /// Number of regions.
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+
+    /// Returns true if the matrix has no regions.
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+
+    /// Manhattan distance between regions `i` and `j`.
+    pub fn distance(&self, i: usize, j: usize) -> i64 {
+        self.pair(i, j).0
+    }
+
+    /// Chebyshev distance between regions `i` and `j`.
+    pub fn chebyshev(&self, i: usize, j: usize) -> i64 {
+        self.pair(i, j).1
+    }
+
+    fn pair(&self, i: usize, j: usize) -> (i64, i64) {
+        let n = self.regions.len();
+        assert!(i < n && j < n, "region index out of range: ({}, {}) with {} regions", i, j, n);
+        let (i, j) = if i < j { (i, j) } else { (j, i) };
+        if i == j {
+            return (0, 0);
+        }
+        // Row `i` of the upper triangle starts after the (n-1) + (n-2) + ... + (n-i) earlier pairs.
+        self.pairs[i * (2 * n - i - 1) / 2 + (j - i - 1)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_region_dims_indexing() {
+        let dims = RegionDims::new(RegionCoordinate::new(1, 1, 1), RegionCoordinate::new(-1, -1, -1));
+        assert_eq!(dims.volume(), 27);
+        for index in 0..dims.volume() {
+            assert_eq!(dims.index_of(&dims.coord_at(index)), Some(index));
+        }
+        assert_eq!(dims.index_of(&RegionCoordinate::new(2, 0, 0)), None);
+    }
+
+    #[test]
+    fn test_invalid_dims_rejected() {
+        let inverted = r#"{"min": {"x": 1, "y": 0, "z": 0}, "max": {"x": 0, "y": 0, "z": 0}}"#;
+        assert!(serde_json::from_str::<RegionDims>(inverted).is_err());
+        let literal = RegionDims { min: RegionCoordinate::new(1, 0, 0), max: RegionCoordinate::center() };
+        assert_eq!(literal.volume(), 0);
+        assert!(matches!(RegionOccupancy::new(literal), Err(InvalidRegionDims::Inverted { .. })));
+
+        let huge = RegionDims::new(RegionCoordinate::new(i64::MIN, i64::MIN, 0), RegionCoordinate::new(i64::MAX, 0, 0));
+        assert_eq!(huge.checked_volume(), None);
+        assert_eq!(RegionOccupancy::new(huge), Err(InvalidRegionDims::TooLarge));
+
+        let dims = RegionDims::new(RegionCoordinate::center(), RegionCoordinate::new(9, 9, 0));
+        let occupancy = RegionOccupancy::new(dims).unwrap();
+        let mut json = serde_json::to_value(&occupancy).unwrap();
+        assert_eq!(serde_json::from_value::<RegionOccupancy>(json.clone()).unwrap(), occupancy);
+        json["bits"] = serde_json::json!([]);
+        assert!(serde_json::from_value::<RegionOccupancy>(json).is_err());
+    }
+
+    #[test]
+    fn test_distance_matrix_matches_direct() {
+        let regions = [
+            RegionCoordinate::new(0, 0, 0),
+            RegionCoordinate::new(3, -1, 2),
+            RegionCoordinate::new(-2, 4, 0),
+            RegionCoordinate::new(1, 1, 1),
+            RegionCoordinate::new(-5, 0, -3),
+        ];
+        let matrix = DistanceMatrix::new(&regions);
+        assert_eq!(matrix.len(), 5);
+        for (i, a) in regions.iter().enumerate() {
+            for (j, b) in regions.iter().enumerate() {
+                assert_eq!(matrix.distance(i, j), a.manhattan_distance(b));
+                assert_eq!(matrix.chebyshev(i, j), a.chebyshev_distance(b));
+            }
+        }
+        assert!(DistanceMatrix::new(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_world_snapshot_coverage_gaps() {
+        let mut snapshot = WorldSnapshot::new(Utc::now());
+        for (x, players) in [(0, 12), (2, 30)] {
+            let region = RegionCoordinate::new(x, 0, 0);
+            let info = ServerInfo::new(
+                format!("horizon-{}", x),
+                "127.0.0.1:8080".to_string(),
+                region,
+                RegionBounds::from_center(region.to_world_center(1000.0), 500.0),
+                100,
+            );
+            snapshot.add_server(&info, players);
+        }
+        assert_eq!(snapshot.total_players(), 42);
+
+        let restored = WorldSnapshot::from_json(&snapshot.to_json().unwrap()).unwrap();
+        assert_eq!(restored, snapshot);
+
+        let dims = RegionDims::new(RegionCoordinate::new(0, 0, 0), RegionCoordinate::new(2, 0, 0));
+        assert_eq!(restored.coverage_gaps(&dims).unwrap(), vec![RegionCoordinate::new(1, 0, 0)]);
+    }
+
+    #[test]
+    fn test_servers_within_hops() {
+        let mut grid = RegionGrid::new();
+        for x in 0..3 {
+            for y in 0..3 {
+                grid.insert(RegionCoordinate::new(x, y, 0), ServerId::from(format!("s{}{}", x, y)));
+            }
+        }
+
+        let mut corner = servers_within_hops(&grid, &RegionCoordinate::new(0, 0, 0), 1);
+        assert_eq!(corner[0], ServerId::from("s00"));
+        corner.sort_by(|a, b| a.0.cmp(&b.0));
+        let ids: Vec<&str> = corner.iter().map(|s| s.as_str()).collect();
+        assert_eq!(ids, vec!["s00", "s01", "s10", "s11"]);
+
+        assert_eq!(servers_within_hops(&grid, &RegionCoordinate::new(1, 1, 0), 1).len(), 9);
+        assert_eq!(servers_within_hops(&grid, &RegionCoordinate::new(1, 1, 0), 0), vec![ServerId::from("s11")]);
+        assert!(servers_within_hops(&grid, &RegionCoordinate::new(5, 5, 0), 1).is_empty());
+    }
+
+    #[test]
+    fn test_region_occupancy() {
+        let dims = RegionDims::new(RegionCoordinate::new(0, 0, 0), RegionCoordinate::new(1, 1, 0));
+        let mut occupancy = RegionOccupancy::new(dims).unwrap();
+        assert!(occupancy.set(&RegionCoordinate::new(0, 0, 0), true));
+        assert!(occupancy.set(&RegionCoordinate::new(1, 1, 0), true));
+        assert!(!occupancy.set(&RegionCoordinate::new(5, 0, 0), true));
+
+        assert!(occupancy.is_occupied(&RegionCoordinate::new(1, 1, 0)));
+        assert!(!occupancy.is_occupied(&RegionCoordinate::new(1, 0, 0)));
+        assert_eq!(occupancy.occupied_count(), 2);
+        assert_eq!(
+            occupancy.uncovered_regions(),
+            vec![RegionCoordinate::new(1, 0, 0), RegionCoordinate::new(0, 1, 0)]
+        );
+
+        occupancy.set(&RegionCoordinate::new(0, 0, 0), false);
+        assert!(!occupancy.is_occupied(&RegionCoordinate::new(0, 0, 0)));
+    }
+
+    #[test]
+    fn test_nearest_occupied() {
+        let dims = RegionDims::new(RegionCoordinate::new(-5, -5, 0), RegionCoordinate::new(5, 5, 0));
+        let mut occupancy = RegionOccupancy::new(dims).unwrap();
+        let from = RegionCoordinate::center();
+        assert_eq!(occupancy.nearest_occupied(&from), None);
+
+        occupancy.set(&RegionCoordinate::new(4, -4, 0), true);
+        occupancy.set(&RegionCoordinate::new(-2, 1, 0), true);
+        occupancy.set(&RegionCoordinate::new(0, 3, 0), true);
+        assert_eq!(occupancy.nearest_occupied(&from), Some(RegionCoordinate::new(-2, 1, 0)));
+
+        // Searching from outside the dims still reaches the far corner.
+        let outside = RegionCoordinate::new(9, -9, 0);
+        assert_eq!(occupancy.nearest_occupied(&outside), Some(RegionCoordinate::new(4, -4, 0)));
+
+        // Far outside the dims the search must not walk every shell in between.
+        let far = RegionCoordinate::new(1_000_000, 0, 0);
+        assert_eq!(occupancy.nearest_occupied(&far), Some(RegionCoordinate::new(4, -4, 0)));
+        let far = RegionCoordinate::new(-1_000_000, 1_000_000, 7);
+        assert_eq!(occupancy.nearest_occupied(&far), Some(RegionCoordinate::new(-2, 1, 0)));
+    }
+
+    #[test]
+    fn test_nearest_occupied_flat_map() {
+        // Shells grow in Z but the map does not, so the search must switch to
+        // the linear scan long before reaching the far corner.
+        let dims = RegionDims::new(RegionCoordinate::center(), RegionCoordinate::new(2047, 2047, 0));
+        let mut occupancy = RegionOccupancy::new(dims).unwrap();
+        let corner = RegionCoordinate::new(2047, 2047, 0);
+        occupancy.set(&corner, true);
+        assert_eq!(occupancy.nearest_occupied(&RegionCoordinate::center()), Some(corner));
+        assert_eq!(occupancy.nearest_occupied(&RegionCoordinate::new(2046, 2047, 0)), Some(corner));
+    }
+}
@@ -5,11 +5,16 @@
 
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
+use std::cmp::Reverse;
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
 
-use crate::server::ServerId;
+use crate::server::{Load, ServerId};
+use crate::spatial::RegionCoordinate;
+use crate::units::Millis;
 
 /// Overall health status of a service.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum HealthStatus {
     /// Service is healthy and operating normally
@@ -19,15 +24,10 @@ pub enum HealthStatus {
     /// Service is not responding or critically failed
     Unhealthy,
     /// Health status is unknown (no recent check)
+    #[default]
     Unknown,
 }
 
-impl Default for HealthStatus {
-    fn default() -> Self {
-        Self::Unknown
-    }
-}
-
 impl HealthStatus {
     /// Returns true if the service is operational (healthy or degraded).
     pub fn is_operational(&self) -> bool {
@@ -106,6 +106,20 @@ impl HealthCheck {
             self.player_count as f32 / self.capacity as f32
         }
     }
+
+    /// Sort key that orders the most problematic servers first.
+    ///
+    /// Checks are ranked by status (unhealthy, unknown, degraded, healthy) and
+    /// then by load, busiest first, so a plain ascending sort yields triage order.
+    pub fn triage_key(&self) -> (u8, Reverse<Load>) {
+        let rank = match self.status {
+            HealthStatus::Unhealthy => 0,
+            HealthStatus::Unknown => 1,
+            HealthStatus::Degraded => 2,
+            HealthStatus::Healthy => 3,
+        };
+        (rank, Reverse(Load::from_ratio(self.player_count, self.capacity)))
+    }
 }
 
 /// Health status of an individual component.
@@ -118,9 +132,9 @@ pub struct ComponentHealth {
     /// Optional details
     #[serde(default)]
     pub details: Option<String>,
-    /// Response time in milliseconds (if applicable)
+    /// Response time (if applicable)
     #[serde(default)]
-    pub response_time_ms: Option<u64>,
+    pub response_time_ms: Option<Millis>,
 }
 
 impl ComponentHealth {
@@ -146,7 +160,7 @@ impl ComponentHealth {
 }
 
 /// Health check request.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct HealthCheckRequest {
     /// Whether to include detailed component checks
     #[serde(default)]
@@ -156,15 +170,6 @@ pub struct HealthCheckRequest {
     pub include_metrics: bool,
 }
 
-impl Default for HealthCheckRequest {
-    fn default() -> Self {
-        Self {
-            include_components: false,
-            include_metrics: false,
-        }
-    }
-}
-
 /// Aggregated health status for all servers (used by Atlas).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClusterHealth {
@@ -224,6 +229,40 @@ impl ClusterHealth {
         }
     }
 
+    /// Derives a cluster status where each server counts in proportion to its players.
+    ///
+    /// Servers are scored healthy = 0, degraded = 1, unhealthy/unknown = 2 and
+    /// the player-weighted average is rounded back to a status, so one busy
+    /// degraded server outweighs many nearly empty healthy ones. Falls back to
+    /// the unweighted status when no server has players.
+    pub fn player_weighted_status(checks: &[HealthCheck]) -> HealthStatus {
+        let total_players: u64 = checks.iter().map(|c| c.player_count as u64).sum();
+        if total_players == 0 {
+            return Self::new(checks).status;
+        }
+
+        let weighted: f64 = checks
+            .iter()
+            .map(|c| {
+                let severity = match c.status {
+                    HealthStatus::Healthy => 0.0,
+                    HealthStatus::Degraded => 1.0,
+                    HealthStatus::Unhealthy | HealthStatus::Unknown => 2.0,
+                };
+                severity * c.player_count as f64
+            })
+            .sum::<f64>()
+            / total_players as f64;
+
+        if weighted < 0.5 {
+            HealthStatus::Healthy
+        } else if weighted < 1.5 {
+            HealthStatus::Degraded
+        } else {
+            HealthStatus::Unhealthy
+        }
+    }
+
     /// Calculates overall load factor.
     pub fn load_factor(&self) -> f32 {
         if self.total_capacity == 0 {
@@ -232,6 +271,282 @@ impl ClusterHealth {
             self.total_players as f32 / self.total_capacity as f32
         }
     }
+
+    /// Fraction of servers that are operational (0.0 to 1.0).
+    ///
+    /// Healthy and degraded servers count as available. Returns 0.0 when the
+    /// cluster has no servers.
+    pub fn availability(&self) -> f32 {
+        let available = self.healthy_servers + self.degraded_servers;
+        let total = available + self.unhealthy_servers;
+        if total == 0 {
+            0.0
+        } else {
+            available as f32 / total as f32
+        }
+    }
+}
+
+/// Health of the servers backing a single region.
+///
+/// A region may be served by several servers for redundancy. The first check
+/// passed in is taken to be the primary; the rest are standbys.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegionClusterHealth {
+    /// Region the servers back
+    pub region: RegionCoordinate,
+    /// Primary server, if any checks were given
+    pub primary: Option<ServerId>,
+    /// Status of the primary server
+    pub primary_status: HealthStatus,
+    /// Counters over every backing server
+    #[serde(flatten)]
+    pub health: ClusterHealth,
+}
+
+impl RegionClusterHealth {
+    /// Aggregates the health checks of one region's backing servers, primary first.
+    pub fn new(region: RegionCoordinate, checks: &[HealthCheck]) -> Self {
+        let primary = checks.first();
+        Self {
+            region,
+            primary: primary.map(|c| c.server_id.clone()),
+            primary_status: primary.map_or(HealthStatus::Unknown, |c| c.status),
+            health: ClusterHealth::new(checks),
+        }
+    }
+
+    /// Returns true if the primary server is healthy.
+    pub fn primary_healthy(&self) -> bool {
+        self.primary_status == HealthStatus::Healthy
+    }
+}
+
+/// Resource totals across all servers, aggregated from health checks.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct ClusterResources {
+    /// Sum of server memory usage in megabytes
+    pub total_memory_mb: u64,
+    /// Mean CPU usage percentage across servers
+    pub avg_cpu_percent: f32,
+    /// Sum of server tick rates (ticks per second)
+    pub total_tick_capacity: f32,
+}
+
+impl ClusterResources {
+    /// Aggregates resources from the latest health check of each server.
+    pub fn from_health(checks: &[HealthCheck]) -> Self {
+        if checks.is_empty() {
+            return Self::default();
+        }
+        Self {
+            total_memory_mb: checks.iter().map(|c| c.memory_mb as u64).sum(),
+            avg_cpu_percent: checks.iter().map(|c| c.cpu_percent).sum::<f32>() / checks.len() as f32,
+            total_tick_capacity: checks.iter().map(|c| c.tick_rate).sum(),
+        }
+    }
+
+    /// Renders the totals in the Prometheus text exposition format.
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+        let mut gauge = |name: &str, help: &str, value: String| {
+            out.push_str(&format!("# HELP {name} {help}\n# TYPE {name} gauge\n{name} {value}\n"));
+        };
+        gauge(
+            "horizon_cluster_memory_mb",
+            "Total memory used by Horizon servers in megabytes.",
+            self.total_memory_mb.to_string(),
+        );
+        gauge(
+            "horizon_cluster_cpu_percent",
+            "Average CPU usage of Horizon servers.",
+            self.avg_cpu_percent.to_string(),
+        );
+        gauge(
+            "horizon_cluster_tick_capacity",
+            "Total ticks per second across Horizon servers.",
+            self.total_tick_capacity.to_string(),
+        );
+        out
+    }
+}
+
+/// Recent health statuses of a single server, oldest first.
+///
+/// Deserialization rejects histories holding more statuses than their capacity.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "RawHealthHistory")]
+pub struct HealthHistory {
+    /// Maximum number of statuses kept
+    capacity: usize,
+    statuses: VecDeque<HealthStatus>,
+}
+
+/// Error returned when a serialized [`HealthHistory`] exceeds its capacity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("Health history holds {len} statuses but its capacity is {capacity}")]
+pub struct InvalidHealthHistory {
+    pub len: usize,
+    pub capacity: usize,
+}
+
+#[derive(Deserialize)]
+struct RawHealthHistory {
+    capacity: usize,
+    statuses: VecDeque<HealthStatus>,
+}
+
+impl TryFrom<RawHealthHistory> for HealthHistory {
+    type Error = InvalidHealthHistory;
+
+    fn try_from(raw: RawHealthHistory) -> Result<Self, Self::Error> {
+        if raw.statuses.len() > raw.capacity {
+            return Err(InvalidHealthHistory { len: raw.statuses.len(), capacity: raw.capacity });
+        }
+        Ok(Self { capacity: raw.capacity, statuses: raw.statuses })
+    }
+}
+
+impl HealthHistory {
+    /// Creates an empty history keeping at most `capacity` statuses.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            statuses: VecDeque::new(),
+        }
+    }
+
+    /// Maximum number of statuses kept.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Records a new status, dropping the oldest ones if full.
+    pub fn record(&mut self, status: HealthStatus) {
+        while !self.statuses.is_empty() && self.statuses.len() >= self.capacity {
+            self.statuses.pop_front();
+        }
+        if self.capacity > 0 {
+            self.statuses.push_back(status);
+        }
+    }
+
+    /// Most recent status, if any.
+    pub fn latest(&self) -> Option<HealthStatus> {
+        self.statuses.back().copied()
+    }
+
+    /// Number of most recent consecutive statuses matching `predicate`.
+    pub fn trailing(&self, predicate: impl Fn(HealthStatus) -> bool) -> usize {
+        self.statuses.iter().rev().take_while(|&&s| predicate(s)).count()
+    }
+}
+
+/// Thresholds for recommending that a server be drained or evicted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EvictionPolicy {
+    /// Consecutive non-healthy checks before draining
+    pub drain_after: usize,
+    /// Consecutive unhealthy checks before evicting
+    pub evict_after: usize,
+}
+
+impl Default for EvictionPolicy {
+    fn default() -> Self {
+        Self {
+            drain_after: 3,
+            evict_after: 5,
+        }
+    }
+}
+
+/// Recommended action for a server based on its health history.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EvictionDecision {
+    /// Leave the server in rotation
+    Keep,
+    /// Stop routing new players and migrate existing ones away
+    Drain,
+    /// Remove the server from the cluster
+    Evict,
+}
+
+/// Recommends draining on sustained degradation and eviction on sustained failure.
+///
+/// As in [`ClusterHealth`], an `Unknown` status counts as unhealthy.
+pub fn should_evict(history: &HealthHistory, policy: &EvictionPolicy) -> EvictionDecision {
+    let unhealthy = history.trailing(|s| matches!(s, HealthStatus::Unhealthy | HealthStatus::Unknown));
+    let not_healthy = history.trailing(|s| s != HealthStatus::Healthy);
+
+    if policy.evict_after > 0 && unhealthy >= policy.evict_after {
+        EvictionDecision::Evict
+    } else if policy.drain_after > 0 && not_healthy >= policy.drain_after {
+        EvictionDecision::Drain
+    } else {
+        EvictionDecision::Keep
+    }
+}
+
+/// Number of heartbeat intervals kept per server by [`HeartbeatMonitor`].
+const HEARTBEAT_WINDOW: usize = 32;
+
+#[derive(Debug, Clone, Default)]
+struct HeartbeatTiming {
+    last: Option<DateTime<Utc>>,
+    intervals_ms: VecDeque<f64>,
+}
+
+/// Tracks heartbeat inter-arrival times to spot GC pauses or network trouble.
+#[derive(Debug, Clone, Default)]
+pub struct HeartbeatMonitor {
+    servers: HashMap<ServerId, HeartbeatTiming>,
+}
+
+impl HeartbeatMonitor {
+    /// Creates an empty monitor.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a heartbeat arrival from a server.
+    pub fn record(&mut self, server_id: &ServerId, now: DateTime<Utc>) {
+        let timing = self.servers.entry(server_id.clone()).or_default();
+        if let Some(last) = timing.last {
+            if timing.intervals_ms.len() == HEARTBEAT_WINDOW {
+                timing.intervals_ms.pop_front();
+            }
+            let interval = (now - last).num_microseconds().unwrap_or(i64::MAX) as f64 / 1000.0;
+            timing.intervals_ms.push_back(interval.max(0.0));
+        }
+        timing.last = Some(now);
+    }
+
+    /// Standard deviation of recent heartbeat intervals.
+    ///
+    /// Returns None until at least two intervals have been recorded.
+    pub fn jitter(&self, server_id: &ServerId) -> Option<Duration> {
+        let intervals = &self.servers.get(server_id)?.intervals_ms;
+        if intervals.len() < 2 {
+            return None;
+        }
+        let n = intervals.len() as f64;
+        let mean = intervals.iter().sum::<f64>() / n;
+        let variance = intervals.iter().map(|i| (i - mean).powi(2)).sum::<f64>() / n;
+        Some(Duration::from_secs_f64(variance.sqrt() / 1000.0))
+    }
+
+    /// Returns true if the latest interval or the jitter exceeds `tolerance`.
+    ///
+    /// The latest interval is compared against `expected_interval`.
+    pub fn is_anomalous(&self, server_id: &ServerId, expected_interval: Duration, tolerance: Duration) -> bool {
+        let Some(latest) = self.servers.get(server_id).and_then(|t| t.intervals_ms.back()) else {
+            return false;
+        };
+        let deviation_ms = (latest - expected_interval.as_secs_f64() * 1000.0).abs();
+        deviation_ms > tolerance.as_secs_f64() * 1000.0
+            || self.jitter(server_id).is_some_and(|jitter| jitter > tolerance)
+    }
 }
 
 #[cfg(test)]
@@ -246,11 +561,105 @@ mod tests {
         assert!(!HealthStatus::Unknown.is_operational());
     }
 
+    #[test]
+    fn test_triage_order() {
+        let busy = HealthCheck::healthy(ServerId::from("busy"), 90, 100);
+        let quiet = HealthCheck::healthy(ServerId::from("quiet"), 10, 100);
+        let mut degraded = HealthCheck::healthy(ServerId::from("degraded"), 5, 100);
+        degraded.status = HealthStatus::Degraded;
+        let mut stale = HealthCheck::healthy(ServerId::from("stale"), 50, 100);
+        stale.status = HealthStatus::Unknown;
+        let down = HealthCheck::unhealthy(ServerId::from("down"), "crashed".to_string());
+
+        let mut checks = [quiet, degraded, busy, down, stale];
+        checks.sort_by_key(HealthCheck::triage_key);
+        let order: Vec<&str> = checks.iter().map(|c| c.server_id.0.as_str()).collect();
+        assert_eq!(order, vec!["down", "stale", "degraded", "busy", "quiet"]);
+    }
+
+    #[test]
+    fn test_player_weighted_status() {
+        let mut checks: Vec<HealthCheck> = (0..10)
+            .map(|_| HealthCheck::healthy(ServerId::new(), 2, 100))
+            .collect();
+        let mut big = HealthCheck::healthy(ServerId::new(), 1000, 1000);
+        big.status = HealthStatus::Degraded;
+        checks.push(big);
+
+        assert_eq!(ClusterHealth::player_weighted_status(&checks), HealthStatus::Degraded);
+        assert_eq!(
+            ClusterHealth::player_weighted_status(&checks[..10]),
+            HealthStatus::Healthy
+        );
+    }
+
+    #[test]
+    fn test_heartbeat_monitor_regular_and_irregular() {
+        let mut monitor = HeartbeatMonitor::new();
+        let regular = ServerId::from("regular");
+        let irregular = ServerId::from("irregular");
+        let start = Utc::now();
+        let offsets_ms = [0, 5_000, 10_000, 15_000, 20_000];
+        let jittery_ms = [0, 5_000, 7_000, 15_000, 20_000];
+        for (&a, &b) in offsets_ms.iter().zip(jittery_ms.iter()) {
+            monitor.record(&regular, start + chrono::Duration::milliseconds(a));
+            monitor.record(&irregular, start + chrono::Duration::milliseconds(b));
+        }
+
+        let expected = Duration::from_secs(5);
+        let tolerance = Duration::from_millis(500);
+        assert_eq!(monitor.jitter(&regular), Some(Duration::ZERO));
+        assert!(!monitor.is_anomalous(&regular, expected, tolerance));
+        assert!(monitor.jitter(&irregular).unwrap() > Duration::from_secs(1));
+        assert!(monitor.is_anomalous(&irregular, expected, tolerance));
+        assert_eq!(monitor.jitter(&ServerId::from("unknown")), None);
+    }
+
+    #[test]
+    fn test_eviction_recommendation() {
+        let policy = EvictionPolicy::default();
+        let mut history = HealthHistory::new(10);
+        let mut decisions = Vec::new();
+        for status in [
+            HealthStatus::Healthy,
+            HealthStatus::Degraded,
+            HealthStatus::Degraded,
+            HealthStatus::Degraded,
+            HealthStatus::Unhealthy,
+            HealthStatus::Unhealthy,
+            HealthStatus::Unhealthy,
+            HealthStatus::Unhealthy,
+            HealthStatus::Unhealthy,
+            HealthStatus::Healthy,
+        ] {
+            history.record(status);
+            decisions.push(should_evict(&history, &policy));
+        }
+        use EvictionDecision::*;
+        assert_eq!(decisions, vec![Keep, Keep, Keep, Drain, Drain, Drain, Drain, Drain, Evict, Keep]);
+    }
+
+    #[test]
+    fn test_health_history_capacity() {
+        let mut history = HealthHistory::new(2);
+        for status in [HealthStatus::Healthy, HealthStatus::Degraded, HealthStatus::Unhealthy] {
+            history.record(status);
+        }
+        assert_eq!(history.capacity(), 2);
+        assert_eq!(history.trailing(|_| true), 2);
+        assert_eq!(history.latest(), Some(HealthStatus::Unhealthy));
+
+        let json = serde_json::to_string(&history).unwrap();
+        assert_eq!(serde_json::from_str::<HealthHistory>(&json).unwrap().trailing(|_| true), 2);
+        let overfull = r#"{"capacity": 1, "statuses": ["healthy", "healthy"]}"#;
+        assert!(serde_json::from_str::<HealthHistory>(overfull).is_err());
+    }
+
     #[test]
     fn test_cluster_health_aggregation() {
         let server_id = ServerId::new();
         let checks = vec![
-            HealthCheck::healthy(server_id, 50, 100),
+            HealthCheck::healthy(server_id.clone(), 50, 100),
             HealthCheck::healthy(server_id, 30, 100),
         ];
         let cluster = ClusterHealth::new(&checks);
@@ -258,4 +667,66 @@ mod tests {
         assert_eq!(cluster.total_players, 80);
         assert_eq!(cluster.total_capacity, 200);
     }
+
+    #[test]
+    fn test_region_cluster_health() {
+        let region = RegionCoordinate::new(2, 0, 1);
+        let primary = ServerId::from("horizon-primary");
+        let checks = [
+            HealthCheck::healthy(primary.clone(), 40, 100),
+            HealthCheck::unhealthy(ServerId::from("horizon-standby"), "tick stalled".to_string()),
+        ];
+        let health = RegionClusterHealth::new(region, &checks);
+        assert_eq!(health.primary, Some(primary));
+        assert!(health.primary_healthy());
+        assert_eq!(health.health.healthy_servers, 1);
+        assert_eq!(health.health.unhealthy_servers, 1);
+        assert_eq!(health.health.status, HealthStatus::Degraded);
+
+        let json = serde_json::to_value(&health).unwrap();
+        assert_eq!(json["healthy_servers"], 1);
+
+        let failed_over = RegionClusterHealth::new(region, &[checks[1].clone(), checks[0].clone()]);
+        assert!(!failed_over.primary_healthy());
+        assert!(!RegionClusterHealth::new(region, &[]).primary_healthy());
+    }
+
+    #[test]
+    fn test_cluster_resources() {
+        let checks: Vec<HealthCheck> = [(512, 20.0), (1024, 50.0), (256, 80.0)]
+            .into_iter()
+            .map(|(memory_mb, cpu_percent)| {
+                let mut check = HealthCheck::healthy(ServerId::new(), 10, 100);
+                check.memory_mb = memory_mb;
+                check.cpu_percent = cpu_percent;
+                check
+            })
+            .collect();
+
+        let resources = ClusterResources::from_health(&checks);
+        assert_eq!(resources.total_memory_mb, 1792);
+        assert!((resources.avg_cpu_percent - 50.0).abs() < 0.001);
+        assert_eq!(resources.total_tick_capacity, 180.0);
+        assert_eq!(ClusterResources::from_health(&[]), ClusterResources::default());
+
+        let text = resources.to_prometheus();
+        assert!(text.contains("# TYPE horizon_cluster_memory_mb gauge\nhorizon_cluster_memory_mb 1792\n"));
+        assert!(text.contains("horizon_cluster_cpu_percent 50\n"));
+        assert!(text.contains("horizon_cluster_tick_capacity 180\n"));
+    }
+
+    #[test]
+    fn test_cluster_availability() {
+        let healthy: Vec<HealthCheck> = (0..4)
+            .map(|_| HealthCheck::healthy(ServerId::new(), 10, 100))
+            .collect();
+        assert_eq!(ClusterHealth::new(&healthy).availability(), 1.0);
+
+        let mut half_down = healthy;
+        half_down[0].status = HealthStatus::Degraded;
+        half_down[2] = HealthCheck::unhealthy(ServerId::new(), "crashed".to_string());
+        half_down[3].status = HealthStatus::Unknown;
+        assert_eq!(ClusterHealth::new(&half_down).availability(), 0.5);
+        assert_eq!(ClusterHealth::new(&[]).availability(), 0.0);
+    }
 }
@@ -0,0 +1,213 @@
+//! Interest management types.
+//!
+//! Instead of running a radius query per player every tick, players subscribe
+//! to grid cells and receive updates from the cells around them.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use crate::player::PlayerId;
+use crate::server::ServerId;
+use crate::spatial::{RegionBounds, RegionCoordinate, WorldCoordinate, WorldGrid};
+
+/// Cluster-wide interest management policy pushed by Atlas to every server.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct InterestConfig {
+    /// View distance used when a player has no override
+    pub default_view_distance: f64,
+    /// Largest view distance a player may request
+    pub max_view_distance: f64,
+    /// Edge length of an interest cell in world units
+    pub cell_size: f64,
+}
+
+/// Error returned by [`InterestConfig::validate`].
+#[derive(Debug, Clone, Copy, PartialEq, thiserror::Error)]
+pub enum InvalidInterestConfig {
+    /// Cell size is zero, negative, infinite or NaN
+    #[error("cell_size must be positive, got {0}")]
+    NonPositiveCellSize(f64),
+
+    /// Default view distance is negative or NaN
+    #[error("default_view_distance must be non-negative, got {0}")]
+    NegativeDefault(f64),
+
+    /// Max view distance is NaN or below the default
+    #[error("max_view_distance ({max}) must be at least default_view_distance ({default})")]
+    MaxBelowDefault { max: f64, default: f64 },
+}
+
+impl InterestConfig {
+    /// Checks the configuration is usable.
+    pub fn validate(&self) -> Result<(), InvalidInterestConfig> {
+        if !self.cell_size.is_finite() || self.cell_size <= 0.0 {
+            return Err(InvalidInterestConfig::NonPositiveCellSize(self.cell_size));
+        }
+        if self.default_view_distance.is_nan() || self.default_view_distance < 0.0 {
+            return Err(InvalidInterestConfig::NegativeDefault(self.default_view_distance));
+        }
+        if self.max_view_distance.is_nan() || self.max_view_distance < self.default_view_distance {
+            return Err(InvalidInterestConfig::MaxBelowDefault {
+                max: self.max_view_distance,
+                default: self.default_view_distance,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Read-only replica of a player simulated by a neighbouring server.
+///
+/// Servers replicate ("ghost") players near a region boundary to the server
+/// on the other side so that server's clients can see them. Only the
+/// authoritative server may change the player's state.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GhostEntity {
+    /// Replicated player
+    pub player_id: PlayerId,
+    /// Position in world coordinates
+    pub position: WorldCoordinate,
+    /// Velocity for extrapolation between updates
+    pub velocity: WorldCoordinate,
+    /// Server simulating the player
+    pub authoritative_server: ServerId,
+}
+
+/// Selects the ghosts within `max_distance` of the edge of `bounds`.
+///
+/// Works for ghosts on either side of the boundary, so a server can use it
+/// both to pick which of its players to ghost out and to discard incoming
+/// ghosts too far away to be visible.
+pub fn ghosts_near_boundary<'a>(
+    ghosts: &'a [GhostEntity],
+    bounds: &'a RegionBounds,
+    max_distance: f64,
+) -> impl Iterator<Item = &'a GhostEntity> + 'a {
+    ghosts
+        .iter()
+        .filter(move |g| bounds.distance_to_boundary(&g.position).abs() <= max_distance)
+}
+
+/// Maps grid cells to the players subscribed to them.
+#[derive(Debug, Clone, Default)]
+pub struct CellSubscriptions {
+    cells: HashMap<RegionCoordinate, HashSet<PlayerId>>,
+}
+
+impl CellSubscriptions {
+    /// Creates an empty subscription table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Subscribes a player to a cell. Returns false if already subscribed.
+    pub fn subscribe(&mut self, cell: RegionCoordinate, player_id: PlayerId) -> bool {
+        self.cells.entry(cell).or_default().insert(player_id)
+    }
+
+    /// Unsubscribes a player from a cell. Returns false if not subscribed.
+    pub fn unsubscribe(&mut self, cell: &RegionCoordinate, player_id: &PlayerId) -> bool {
+        let Some(subscribers) = self.cells.get_mut(cell) else {
+            return false;
+        };
+        let removed = subscribers.remove(player_id);
+        if subscribers.is_empty() {
+            self.cells.remove(cell);
+        }
+        removed
+    }
+
+    /// Gets the players subscribed to a single cell.
+    pub fn subscribers(&self, cell: &RegionCoordinate) -> impl Iterator<Item = &PlayerId> {
+        self.cells.get(cell).into_iter().flatten()
+    }
+
+    /// Gathers subscribers of the cell containing `coord` and its 26 neighbors.
+    pub fn subscribers_near(&self, coord: &WorldCoordinate, grid: &WorldGrid) -> Vec<PlayerId> {
+        let center = grid.region_of(coord);
+        let mut found = HashSet::new();
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    let cell = RegionCoordinate::new(center.x + dx, center.y + dy, center.z + dz);
+                    found.extend(self.subscribers(&cell).cloned());
+                }
+            }
+        }
+        found.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ghosts_near_boundary() {
+        let bounds = RegionBounds::new(0.0, 100.0, 0.0, 100.0, 0.0, 100.0);
+        let ghost = |id: &str, x: f64| GhostEntity {
+            player_id: PlayerId::from(id),
+            position: WorldCoordinate::new(x, 50.0, 50.0),
+            velocity: WorldCoordinate::zero(),
+            authoritative_server: ServerId::from("horizon-2"),
+        };
+        let ghosts = vec![ghost("outside-near", 105.0), ghost("inside-near", 95.0), ghost("far", 160.0), ghost("center", 50.0)];
+
+        let near: Vec<&str> = ghosts_near_boundary(&ghosts, &bounds, 10.0)
+            .map(|g| g.player_id.as_str())
+            .collect();
+        assert_eq!(near, vec!["outside-near", "inside-near"]);
+    }
+
+    #[test]
+    fn test_interest_config_validation() {
+        let config = InterestConfig {
+            default_view_distance: 200.0,
+            max_view_distance: 500.0,
+            cell_size: 100.0,
+        };
+        assert!(config.validate().is_ok());
+        assert_eq!(
+            InterestConfig { max_view_distance: 100.0, ..config }.validate(),
+            Err(InvalidInterestConfig::MaxBelowDefault { max: 100.0, default: config.default_view_distance })
+        );
+        assert_eq!(
+            InterestConfig { cell_size: 0.0, ..config }.validate(),
+            Err(InvalidInterestConfig::NonPositiveCellSize(0.0))
+        );
+        assert!(matches!(
+            InterestConfig { default_view_distance: f64::NAN, ..config }.validate(),
+            Err(InvalidInterestConfig::NegativeDefault(d)) if d.is_nan()
+        ));
+    }
+
+    #[test]
+    fn test_subscribe_and_unsubscribe() {
+        let mut subs = CellSubscriptions::new();
+        let player = PlayerId::from("player-1");
+        let cell = RegionCoordinate::new(0, 0, 0);
+        assert!(subs.subscribe(cell, player.clone()));
+        assert!(!subs.subscribe(cell, player.clone()));
+        assert_eq!(subs.subscribers(&cell).count(), 1);
+        assert!(subs.unsubscribe(&cell, &player));
+        assert!(!subs.unsubscribe(&cell, &player));
+        assert_eq!(subs.subscribers(&cell).count(), 0);
+    }
+
+    #[test]
+    fn test_subscribers_near_gathers_neighborhood() {
+        let grid = WorldGrid::new(100.0).unwrap();
+        let mut subs = CellSubscriptions::new();
+        subs.subscribe(RegionCoordinate::new(0, 0, 0), PlayerId::from("center"));
+        subs.subscribe(RegionCoordinate::new(1, 1, 1), PlayerId::from("corner"));
+        subs.subscribe(RegionCoordinate::new(-1, 0, 0), PlayerId::from("face"));
+        subs.subscribe(RegionCoordinate::new(2, 0, 0), PlayerId::from("far"));
+
+        let near: HashSet<_> = subs
+            .subscribers_near(&WorldCoordinate::new(50.0, 50.0, 50.0), &grid)
+            .into_iter()
+            .collect();
+        let expected: HashSet<_> = ["center", "corner", "face"].into_iter().map(PlayerId::from).collect();
+        assert_eq!(near, expected);
+    }
+}
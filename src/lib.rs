@@ -31,21 +31,35 @@
 //! ```
 
 pub mod spatial;
+pub mod grid;
 pub mod server;
 pub mod player;
 pub mod transfer;
 pub mod health;
 pub mod messages;
+pub mod schema;
+pub mod codec;
+pub mod error;
+pub mod scaling;
+pub mod admission;
+pub mod interest;
+pub mod cluster;
+pub mod units;
+pub mod util;
 
 // Re-export commonly used types
 
 // Spatial types
-pub use spatial::{WorldCoordinate, RegionCoordinate, RegionBounds};
+pub use spatial::{Axis, WorldCoordinate, RegionCoordinate, RegionBounds, WorldGrid, InvalidRegionSize, StampedRegion, FloatingOrigin, WorldLimits, InvalidWorldLimits, TilingError, centroid};
+
+// Dense region grid types
+pub use grid::{RegionDims, RegionOccupancy, InvalidRegionDims, MAX_OCCUPANCY_REGIONS, RegionGrid, DistanceMatrix, WorldSnapshot, ServerSnapshot, servers_within_hops};
 
 // Server types (full structured types)
 pub use server::{
-    ServerId, ServerInfo, ServerStatus, ServerRegistration, ServerHeartbeat, 
-    RegistrationResponse, SpawnServerRequest, SpawnServerResponse,
+    ServerId, ServerInfo, ServerStatus, ServerRegistration, ServerHeartbeat, Load, InvalidLoad,
+    RegistrationResponse, RegistrationFailureReason, SpawnServerRequest, SpawnServerResponse,
+    ShutdownReason, Transport, InferredStatus, infer_status, negotiate_timeout,
 };
 
 // API-compatible types (flat structures for REST APIs)
@@ -55,13 +69,56 @@ pub use server::{
 };
 
 // Player types  
-pub use player::{PlayerId, PlayerInfo, PlayerState, AuthenticationStatus, ConnectionState, MovementData, DisconnectReason};
+pub use player::{
+    PlayerId, PlayerInfo, PlayerState, AuthenticationStatus, ConnectionState, MovementData, DisconnectReason,
+    PlayerStateBuilder, PlayerStateError, ReconnectToken, TransferFields, UnknownTransferFields, reconnect_grace_secs,
+};
 
 // Transfer types
-pub use transfer::{TransferToken, TransferRequest, TransferResult, TransferReason, TransferError, TransferNotification};
+pub use transfer::{
+    TransferToken, TransferRequest, TransferResult, TransferReason, TransferError, TransferNotification,
+    RegionHandoffState, BatchTransferToken, TransferEnvelope, TransferPhase, TransferSession,
+    TransferMetrics, TransferEvent, HandoffViolation, validate_handoff, TransferLatencyTracker,
+    BoundaryCrossing, CrossingKind, classify_crossing,
+};
 
 // Health check types
-pub use health::{HealthStatus, HealthCheck, HealthCheckRequest, ComponentHealth, ClusterHealth};
+pub use health::{
+    HealthStatus, HealthCheck, HealthCheckRequest, ComponentHealth, ClusterHealth,
+    HealthHistory, InvalidHealthHistory, EvictionPolicy, EvictionDecision, should_evict, HeartbeatMonitor,
+    ClusterResources, RegionClusterHealth,
+};
+
+// Shared error codes
+pub use error::{ErrorCode, ErrorCategory};
+pub use codec::{DecodeError, DeserializeError, from_json_limited, from_json_slice_limited, from_json_lenient_vec};
 
 // Inter-service message types
-pub use messages::{HorizonMessage, AtlasMessage, AtlasToMaestroMessage, MaestroMessage, Envelope, Ack};
+pub use messages::{
+    HorizonMessage, AtlasMessage, AtlasToMaestroMessage, MaestroMessage, Envelope, Ack, MessageDedup,
+    SizeHistogram, MessagePriority, PendingAcks, ack_timeout, player_list_page, player_list_pages, Message, MessageOrdering,
+};
+
+// Message schema fingerprints
+pub use schema::{message_fingerprint, message_fingerprints};
+
+// Load balancing and scaling helpers
+pub use scaling::{
+    RebalanceTrigger, RegionLoad, InstanceSummary, WarmPool, hottest_region, pick_spawn_region, MAX_SPAWN_SEARCH_RADIUS, balance_score,
+    ScalingPolicy, InvalidScalingPolicy, MAX_INFERRED_COVERAGE_REGIONS, ScalingRecommendation, CapacityReport,
+};
+
+// Player admission types
+pub use admission::{AdmissionController, AdmissionDecision, RejectReason, JoinQueue, QueuedPlayer};
+
+// Interest management types
+pub use interest::{CellSubscriptions, InterestConfig, InvalidInterestConfig, GhostEntity, ghosts_near_boundary};
+
+// Cluster bookkeeping helpers
+pub use cluster::{ConsistencyIssue, check_consistency, AssignmentLedger, AlreadyAssigned};
+
+// Typed time units
+pub use units::{Seconds, Millis};
+
+// Shared utilities
+pub use util::Backoff;
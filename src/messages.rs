@@ -4,17 +4,47 @@
 //! for coordination and control.
 
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::time::{SystemTime, UNIX_EPOCH};
 
+#[cfg(feature = "compression")]
+use crate::codec::{self, DecodeError};
 use crate::health::{HealthCheck, HealthCheckRequest};
-use crate::player::{PlayerId, PlayerState, DisconnectReason};
+use crate::interest::{GhostEntity, InterestConfig};
+use crate::player::{PlayerId, PlayerInfo, PlayerState, DisconnectReason};
 use crate::server::{
     ServerHeartbeat, ServerInfo, ServerRegistration, ServerId,
-    RegistrationResponse, SpawnServerRequest, SpawnServerResponse,
+    RegistrationResponse, SpawnServerRequest, SpawnServerResponse, ShutdownReason,
 };
-use crate::transfer::{TransferRequest, TransferToken};
-use crate::spatial::WorldCoordinate;
+use crate::scaling::RegionLoad;
+use crate::transfer::{TransferError, TransferRequest, TransferToken};
+use crate::spatial::{RegionCoordinate, TilingError, WorldCoordinate};
+
+/// Processing order a message requires from the transport.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageOrdering {
+    /// Must be processed in order with every other strict message on the channel
+    Strict,
+    /// Must be processed in order with other messages about the same player
+    PerPlayer,
+    /// Can be processed in any order, or in parallel
+    None,
+}
+
+/// Common metadata for the message enums.
+pub trait Message {
+    /// Name of the message variant, as used for the serde `type` tag.
+    fn kind(&self) -> &'static str;
+
+    /// Ordering the transport must preserve for this message.
+    fn ordering(&self) -> MessageOrdering;
+}
 
 /// Messages sent from Horizon to Atlas.
+// `Register` is sent once per server lifetime; boxing it would only make
+// construction and matching noisier for every caller.
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", content = "payload")]
 pub enum HorizonMessage {
@@ -61,11 +91,58 @@ pub enum HorizonMessage {
         player_id: PlayerId,
         token_id: String,
     },
+
+    /// Target server declined the transfer before the player moved.
+    ///
+    /// The player stays on the source server. Atlas moves on to the next
+    /// candidate server when `reason.try_alternate_target()` (e.g. the target
+    /// is full or runs an incompatible version), and otherwise holds the
+    /// player where they are.
+    TransferDeclined {
+        token_id: String,
+        player_id: PlayerId,
+        reason: TransferError,
+    },
+
+    /// Transfer rolled back; the source server reclaims the player
+    /// (see `TransferSession::rollback` for when this is safe)
+    TransferRollback {
+        token_id: String,
+        player_id: PlayerId,
+        reason: String,
+    },
     
     /// Server shutting down
     Shutdown {
         server_id: ServerId,
         player_count: u32,
+        #[serde(default)]
+        reason: ShutdownReason,
+    },
+
+    /// Request one page of the cluster player list
+    ///
+    /// `cursor` is None for the first page and otherwise the `next_cursor`
+    /// of the previous `PlayerListPage`.
+    PlayerListRequest {
+        #[serde(default)]
+        cursor: Option<String>,
+        page_size: u32,
+    },
+
+    /// Region ownership released in response to `TransferRegionOwnership`
+    RegionOwnershipReleased {
+        region: RegionCoordinate,
+        player_count: u32,
+    },
+
+    /// Server crossed its capacity warning threshold
+    /// (see `ServerHeartbeat::should_warn`)
+    CapacityWarning {
+        server_id: ServerId,
+        current: u32,
+        capacity: u32,
+        threshold: f32,
     },
 }
 
@@ -112,6 +189,84 @@ pub enum AtlasMessage {
     ConfigUpdate {
         config: serde_json::Value,
     },
+
+    /// Cluster-wide interest management policy
+    InterestConfig(InterestConfig),
+
+    /// One page of the cluster player list
+    PlayerListPage {
+        page: u32,
+        page_size: u32,
+        players: Vec<PlayerInfo>,
+        has_more: bool,
+        /// Opaque cursor to request the next page with, if any
+        #[serde(default)]
+        next_cursor: Option<String>,
+    },
+
+    /// Hand ownership of an entire region to another server (region handoff,
+    /// not a player transfer; see `RegionHandoffState`)
+    TransferRegionOwnership {
+        region: RegionCoordinate,
+        to: ServerId,
+    },
+
+    /// Replicas of players simulated by neighbouring servers
+    GhostUpdate {
+        ghosts: Vec<GhostEntity>,
+    },
+}
+
+/// Answers one `HorizonMessage::PlayerListRequest` with a `PlayerListPage`.
+///
+/// Players are ordered by id and each page starts after the last player of
+/// the previous one, so players joining or leaving between requests do not
+/// shift later pages. A missing or malformed cursor starts from the first
+/// page, and a `page_size` of zero is treated as one.
+pub fn player_list_page(players: &[PlayerInfo], cursor: Option<&str>, page_size: u32) -> AtlasMessage {
+    let mut sorted: Vec<&PlayerInfo> = players.iter().collect();
+    sorted.sort_by(|a, b| a.id.as_str().cmp(b.id.as_str()));
+    page_after_cursor(&sorted, cursor, page_size)
+}
+
+/// Splits a player list into `AtlasMessage::PlayerListPage` messages.
+///
+/// Yields the same pages as following `next_cursor` with
+/// [`player_list_page`], and always at least one, so an empty list still
+/// gets a response.
+pub fn player_list_pages(players: &[PlayerInfo], page_size: u32) -> impl Iterator<Item = AtlasMessage> {
+    let mut sorted: Vec<&PlayerInfo> = players.iter().collect();
+    sorted.sort_by(|a, b| a.id.as_str().cmp(b.id.as_str()));
+    let first = page_after_cursor(&sorted, None, page_size);
+    let mut pages = vec![first];
+    while let Some(AtlasMessage::PlayerListPage { next_cursor: Some(cursor), .. }) = pages.last() {
+        let page = page_after_cursor(&sorted, Some(cursor.as_str()), page_size);
+        pages.push(page);
+    }
+    pages.into_iter()
+}
+
+/// Builds the page following `cursor` from players sorted by id.
+///
+/// The cursor encodes the page number and the id of the last player sent.
+fn page_after_cursor(sorted: &[&PlayerInfo], cursor: Option<&str>, page_size: u32) -> AtlasMessage {
+    let page_size = page_size.max(1);
+    let (page, start) = cursor
+        .and_then(|cursor| cursor.split_once(':'))
+        .and_then(|(page, after)| {
+            let start = sorted.partition_point(|p| p.id.as_str() <= after);
+            Some((page.parse::<u32>().ok()?, start))
+        })
+        .unwrap_or((0, 0));
+    let end = start.saturating_add(page_size as usize).min(sorted.len());
+    let has_more = end < sorted.len();
+    AtlasMessage::PlayerListPage {
+        page,
+        page_size,
+        players: sorted[start..end].iter().map(|p| (*p).clone()).collect(),
+        has_more,
+        next_cursor: has_more.then(|| format!("{}:{}", page.saturating_add(1), sorted[end - 1].id.as_str())),
+    }
 }
 
 /// Messages sent from Atlas to Maestro.
@@ -136,6 +291,11 @@ pub enum AtlasToMaestroMessage {
     ScaleCluster {
         target_count: u32,
     },
+
+    /// Periodic per-region load breakdown for autoscaling
+    RegionLoadReport {
+        regions: Vec<RegionLoad>,
+    },
 }
 
 /// Messages sent from Maestro to Atlas.
@@ -149,6 +309,8 @@ pub enum MaestroMessage {
     ServerStopped {
         instance_id: String,
         exit_code: Option<i32>,
+        #[serde(default)]
+        reason: ShutdownReason,
     },
     
     /// Server stats response
@@ -164,6 +326,18 @@ pub enum MaestroMessage {
         current_count: u32,
         target_count: u32,
     },
+
+    /// A region was subdivided into finer child regions
+    RegionSplit {
+        parent: RegionCoordinate,
+        children: Vec<RegionCoordinate>,
+    },
+
+    /// Child regions were merged back into their parent region
+    RegionMerged {
+        children: Vec<RegionCoordinate>,
+        parent: RegionCoordinate,
+    },
     
     /// Error occurred
     Error {
@@ -172,6 +346,142 @@ pub enum MaestroMessage {
     },
 }
 
+impl Message for HorizonMessage {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Register(_) => "Register",
+            Self::Heartbeat(_) => "Heartbeat",
+            Self::HealthResponse(_) => "HealthResponse",
+            Self::PlayerConnected { .. } => "PlayerConnected",
+            Self::PlayerDisconnected { .. } => "PlayerDisconnected",
+            Self::PlayerPositionUpdate { .. } => "PlayerPositionUpdate",
+            Self::TransferRequest(_) => "TransferRequest",
+            Self::TransferComplete { .. } => "TransferComplete",
+            Self::TransferAccepted { .. } => "TransferAccepted",
+            Self::TransferDeclined { .. } => "TransferDeclined",
+            Self::TransferRollback { .. } => "TransferRollback",
+            Self::Shutdown { .. } => "Shutdown",
+            Self::PlayerListRequest { .. } => "PlayerListRequest",
+            Self::RegionOwnershipReleased { .. } => "RegionOwnershipReleased",
+            Self::CapacityWarning { .. } => "CapacityWarning",
+        }
+    }
+
+    fn ordering(&self) -> MessageOrdering {
+        match self {
+            Self::Register(_) | Self::Shutdown { .. } | Self::RegionOwnershipReleased { .. } => {
+                MessageOrdering::Strict
+            }
+            Self::PlayerConnected { .. }
+            | Self::PlayerDisconnected { .. }
+            | Self::PlayerPositionUpdate { .. }
+            | Self::TransferRequest(_)
+            | Self::TransferComplete { .. }
+            | Self::TransferAccepted { .. }
+            | Self::TransferDeclined { .. }
+            | Self::TransferRollback { .. } => MessageOrdering::PerPlayer,
+            Self::Heartbeat(_)
+            | Self::HealthResponse(_)
+            | Self::PlayerListRequest { .. }
+            | Self::CapacityWarning { .. } => MessageOrdering::None,
+        }
+    }
+}
+
+impl Message for AtlasMessage {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::RegistrationResponse(_) => "RegistrationResponse",
+            Self::HealthCheckRequest(_) => "HealthCheckRequest",
+            Self::InitiateTransfer { .. } => "InitiateTransfer",
+            Self::AcceptTransfer { .. } => "AcceptTransfer",
+            Self::CancelTransfer { .. } => "CancelTransfer",
+            Self::PrepareShutdown { .. } => "PrepareShutdown",
+            Self::AdjacentServersUpdate { .. } => "AdjacentServersUpdate",
+            Self::ConfigUpdate { .. } => "ConfigUpdate",
+            Self::InterestConfig(_) => "InterestConfig",
+            Self::PlayerListPage { .. } => "PlayerListPage",
+            Self::TransferRegionOwnership { .. } => "TransferRegionOwnership",
+            Self::GhostUpdate { .. } => "GhostUpdate",
+        }
+    }
+
+    fn ordering(&self) -> MessageOrdering {
+        match self {
+            Self::RegistrationResponse(_)
+            | Self::PrepareShutdown { .. }
+            | Self::AdjacentServersUpdate { .. }
+            | Self::ConfigUpdate { .. }
+            | Self::InterestConfig(_)
+            | Self::TransferRegionOwnership { .. } => MessageOrdering::Strict,
+            // Batches many players, so there is no single player key to partition on.
+            Self::GhostUpdate { .. } => MessageOrdering::Strict,
+            Self::InitiateTransfer { .. } | Self::AcceptTransfer { .. } | Self::CancelTransfer { .. } => {
+                MessageOrdering::PerPlayer
+            }
+            Self::HealthCheckRequest(_) | Self::PlayerListPage { .. } => MessageOrdering::None,
+        }
+    }
+}
+
+impl Message for AtlasToMaestroMessage {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::SpawnServer(_) => "SpawnServer",
+            Self::StopServer { .. } => "StopServer",
+            Self::GetServerStats { .. } => "GetServerStats",
+            Self::ScaleCluster { .. } => "ScaleCluster",
+            Self::RegionLoadReport { .. } => "RegionLoadReport",
+        }
+    }
+
+    fn ordering(&self) -> MessageOrdering {
+        match self {
+            Self::SpawnServer(_) | Self::StopServer { .. } | Self::ScaleCluster { .. } => MessageOrdering::Strict,
+            Self::GetServerStats { .. } | Self::RegionLoadReport { .. } => MessageOrdering::None,
+        }
+    }
+}
+
+impl Message for MaestroMessage {
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::SpawnResponse(_) => "SpawnResponse",
+            Self::ServerStopped { .. } => "ServerStopped",
+            Self::ServerStats { .. } => "ServerStats",
+            Self::ClusterScaled { .. } => "ClusterScaled",
+            Self::RegionSplit { .. } => "RegionSplit",
+            Self::RegionMerged { .. } => "RegionMerged",
+            Self::Error { .. } => "Error",
+        }
+    }
+
+    fn ordering(&self) -> MessageOrdering {
+        match self {
+            Self::SpawnResponse(_)
+            | Self::ServerStopped { .. }
+            | Self::ClusterScaled { .. }
+            | Self::RegionSplit { .. }
+            | Self::RegionMerged { .. } => MessageOrdering::Strict,
+            Self::ServerStats { .. } | Self::Error { .. } => MessageOrdering::None,
+        }
+    }
+}
+
+impl MaestroMessage {
+    /// Checks that region split/merge events describe a valid tiling.
+    ///
+    /// Other messages are always valid.
+    pub fn validate(&self) -> Result<(), TilingError> {
+        match self {
+            Self::RegionSplit { parent, children } | Self::RegionMerged { children, parent } => {
+                parent.check_tiling(children)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
 /// Wrapper for all message types with metadata.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Envelope<T> {
@@ -183,6 +493,12 @@ pub struct Envelope<T> {
     pub source: String,
     /// Destination service identifier
     pub destination: String,
+    /// Id of the first message in the flow this message belongs to
+    #[serde(default)]
+    pub correlation_id: Option<String>,
+    /// Id of the message that directly caused this one
+    #[serde(default)]
+    pub causation_id: Option<String>,
     /// The actual message
     pub message: T,
 }
@@ -190,8 +506,6 @@ pub struct Envelope<T> {
 impl<T> Envelope<T> {
     /// Creates a new envelope with the given message.
     pub fn new(source: impl Into<String>, destination: impl Into<String>, message: T) -> Self {
-        use std::time::{SystemTime, UNIX_EPOCH};
-        
         Self {
             id: uuid::Uuid::new_v4().to_string(),
             timestamp_ms: SystemTime::now()
@@ -200,9 +514,168 @@ impl<T> Envelope<T> {
                 .as_millis() as u64,
             source: source.into(),
             destination: destination.into(),
+            correlation_id: None,
+            causation_id: None,
             message,
         }
     }
+
+    /// Marks this envelope as caused by `parent`.
+    ///
+    /// The causation id points at the parent, while the correlation id is
+    /// inherited so every message in a multi-hop flow shares the root's id.
+    pub fn caused_by<U>(mut self, parent: &Envelope<U>) -> Self {
+        self.causation_id = Some(parent.id.clone());
+        self.correlation_id = Some(parent.correlation_id.clone().unwrap_or_else(|| parent.id.clone()));
+        self
+    }
+
+    /// Signed difference between the envelope timestamp and `now_ms`.
+    ///
+    /// Positive values mean the sender's clock is ahead of ours (the envelope
+    /// appears to come from the future), negative values that it is behind or
+    /// the message was in flight for a while.
+    pub fn clock_skew_ms(&self, now_ms: u64) -> i64 {
+        (self.timestamp_ms as i128 - now_ms as i128).clamp(i64::MIN as i128, i64::MAX as i128) as i64
+    }
+
+    /// Returns true if the clock skew exceeds `tolerance_ms` in either direction.
+    pub fn is_skewed(&self, now_ms: u64, tolerance_ms: u64) -> bool {
+        self.clock_skew_ms(now_ms).unsigned_abs() > tolerance_ms
+    }
+}
+
+impl<T: Serialize> Envelope<T> {
+    /// Records the serialized size of this envelope in `hist`, returning the size.
+    pub fn record_size(&self, hist: &mut SizeHistogram) -> Result<usize, serde_json::Error> {
+        let size = serde_json::to_vec(self)?.len();
+        hist.record(size);
+        Ok(size)
+    }
+}
+
+/// Leading byte of [`Envelope::to_bytes_compressed`] output for plain JSON.
+#[cfg(feature = "compression")]
+const ENVELOPE_FLAG_PLAIN: u8 = 0;
+
+/// Leading byte of [`Envelope::to_bytes_compressed`] output for DEFLATE-compressed JSON.
+#[cfg(feature = "compression")]
+const ENVELOPE_FLAG_DEFLATE: u8 = 1;
+
+#[cfg(feature = "compression")]
+impl<T: Serialize> Envelope<T> {
+    /// Serializes the envelope, compressing it if the JSON exceeds `threshold` bytes.
+    ///
+    /// The output is a flag byte (0 = plain, 1 = DEFLATE) followed by the
+    /// JSON, so small messages skip the compression overhead. Decode with
+    /// [`Envelope::from_bytes_compressed`].
+    pub fn to_bytes_compressed(&self, threshold: usize) -> std::io::Result<Vec<u8>> {
+        let json = serde_json::to_vec(self)?;
+        let (flag, body) = if json.len() > threshold {
+            (ENVELOPE_FLAG_DEFLATE, codec::compress(&json)?)
+        } else {
+            (ENVELOPE_FLAG_PLAIN, json)
+        };
+        let mut bytes = Vec::with_capacity(body.len() + 1);
+        bytes.push(flag);
+        bytes.extend_from_slice(&body);
+        Ok(bytes)
+    }
+}
+
+#[cfg(feature = "compression")]
+impl<T: serde::de::DeserializeOwned> Envelope<T> {
+    /// Decodes bytes produced by [`Envelope::to_bytes_compressed`].
+    ///
+    /// The JSON may be at most `max_bytes` long; for compressed input the
+    /// limit applies to the decompressed size, so a small payload cannot
+    /// inflate without bound.
+    pub fn from_bytes_compressed(bytes: &[u8], max_bytes: usize) -> Result<Self, DecodeError> {
+        match bytes.split_first() {
+            Some((&ENVELOPE_FLAG_PLAIN, json)) => codec::from_json_slice_limited(json, max_bytes),
+            Some((&ENVELOPE_FLAG_DEFLATE, body)) => {
+                codec::from_json_slice_limited(&codec::decompress(body, max_bytes)?, max_bytes)
+            }
+            Some((&flag, _)) => Err(DecodeError::UnknownFormat(flag)),
+            // Empty input; let serde_json report the EOF.
+            None => codec::from_json_slice_limited(bytes, max_bytes),
+        }
+    }
+}
+
+/// Smallest bucket upper bound of a [`SizeHistogram`], in bytes.
+const SIZE_HISTOGRAM_MIN_BUCKET: usize = 64;
+
+/// Number of power-of-two buckets in a [`SizeHistogram`] (64 B to 16 MiB).
+const SIZE_HISTOGRAM_BUCKETS: usize = 19;
+
+/// Histogram of serialized message sizes for capacity planning.
+///
+/// Sizes are grouped into power-of-two buckets, so percentiles are reported as
+/// the upper bound of the bucket they fall in (capped at the largest recorded size).
+#[derive(Debug, Clone)]
+pub struct SizeHistogram {
+    buckets: [u64; SIZE_HISTOGRAM_BUCKETS + 1],
+    count: u64,
+    max: usize,
+}
+
+impl Default for SizeHistogram {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SizeHistogram {
+    /// Creates an empty histogram.
+    pub fn new() -> Self {
+        Self {
+            buckets: [0; SIZE_HISTOGRAM_BUCKETS + 1],
+            count: 0,
+            max: 0,
+        }
+    }
+
+    /// Records a message size in bytes.
+    pub fn record(&mut self, size: usize) {
+        let index = (0..SIZE_HISTOGRAM_BUCKETS)
+            .find(|&i| size <= SIZE_HISTOGRAM_MIN_BUCKET << i)
+            .unwrap_or(SIZE_HISTOGRAM_BUCKETS);
+        self.buckets[index] += 1;
+        self.count += 1;
+        self.max = self.max.max(size);
+    }
+
+    /// Number of recorded sizes.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Largest recorded size in bytes.
+    pub fn max(&self) -> usize {
+        self.max
+    }
+
+    /// Approximate size at the given percentile (0-100), or None if empty.
+    pub fn percentile(&self, p: f64) -> Option<usize> {
+        if self.count == 0 {
+            return None;
+        }
+        let rank = ((p.clamp(0.0, 100.0) / 100.0) * self.count as f64).ceil().max(1.0) as u64;
+        let mut cumulative = 0;
+        for (i, &n) in self.buckets.iter().enumerate() {
+            cumulative += n;
+            if cumulative >= rank {
+                let upper = if i < SIZE_HISTOGRAM_BUCKETS {
+                    SIZE_HISTOGRAM_MIN_BUCKET << i
+                } else {
+                    self.max
+                };
+                return Some(upper.min(self.max));
+            }
+        }
+        Some(self.max)
+    }
 }
 
 /// Simple acknowledgment response.
@@ -237,10 +710,335 @@ impl Ack {
     }
 }
 
+/// Delivery priority of a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MessagePriority {
+    /// Background traffic that can wait
+    Low,
+    /// Regular traffic
+    #[default]
+    Normal,
+    /// Time-sensitive traffic such as transfers
+    High,
+    /// Traffic that must be delivered as soon as possible
+    Critical,
+}
+
+/// Time to wait for an [`Ack`] before retrying a message.
+///
+/// Scales `base_ms` by priority: critical messages wait a quarter of the base,
+/// high half, normal the full base and low twice as long.
+pub fn ack_timeout(priority: MessagePriority, base_ms: u64) -> u64 {
+    match priority {
+        MessagePriority::Critical => base_ms / 4,
+        MessagePriority::High => base_ms / 2,
+        MessagePriority::Normal => base_ms,
+        MessagePriority::Low => base_ms.saturating_mul(2),
+    }
+}
+
+/// A sent message still waiting for its [`Ack`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PendingAck {
+    priority: MessagePriority,
+    deadline_ms: u64,
+}
+
+/// Tracks sent messages awaiting acknowledgement.
+///
+/// Each message gets a deadline from [`ack_timeout`]; messages past their
+/// deadline are reported by [`PendingAcks::timed_out`] so the sender can retry.
+#[derive(Debug, Clone)]
+pub struct PendingAcks {
+    /// Base ack timeout in milliseconds for normal-priority messages
+    pub base_timeout_ms: u64,
+    pending: HashMap<String, PendingAck>,
+}
+
+impl PendingAcks {
+    /// Creates a tracker with the given base timeout.
+    pub fn new(base_timeout_ms: u64) -> Self {
+        Self {
+            base_timeout_ms,
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Starts waiting for an ack of `message_id`, sent at `sent_at_ms`.
+    ///
+    /// Tracking an id again (e.g. on retry) restarts its deadline.
+    pub fn track(&mut self, message_id: impl Into<String>, priority: MessagePriority, sent_at_ms: u64) {
+        let deadline_ms = sent_at_ms.saturating_add(ack_timeout(priority, self.base_timeout_ms));
+        self.pending.insert(message_id.into(), PendingAck { priority, deadline_ms });
+    }
+
+    /// Records an ack. Returns false if the message was not being tracked.
+    pub fn acknowledge(&mut self, ack: &Ack) -> bool {
+        self.pending.remove(&ack.message_id).is_some()
+    }
+
+    /// Ids of messages whose deadline has passed, highest priority first.
+    pub fn timed_out(&self, now_ms: u64) -> Vec<&str> {
+        let mut expired: Vec<(&String, &PendingAck)> = self
+            .pending
+            .iter()
+            .filter(|(_, p)| now_ms >= p.deadline_ms)
+            .collect();
+        expired.sort_by(|a, b| {
+            b.1.priority
+                .cmp(&a.1.priority)
+                .then(a.1.deadline_ms.cmp(&b.1.deadline_ms))
+                .then(a.0.cmp(b.0))
+        });
+        expired.into_iter().map(|(id, _)| id.as_str()).collect()
+    }
+
+    /// Number of messages awaiting an ack.
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Returns true if no messages are awaiting an ack.
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+/// Detects redelivered envelopes for idempotent processing.
+///
+/// Delivery between services is at-least-once, so consumers can see the same
+/// envelope more than once. Ids are remembered for `ttl_ms` and then forgotten.
+#[derive(Debug, Clone)]
+pub struct MessageDedup {
+    /// How long an id is remembered, in milliseconds
+    pub ttl_ms: u64,
+    seen_at: HashMap<String, u64>,
+    order: VecDeque<(String, u64)>,
+}
+
+impl MessageDedup {
+    /// Creates a dedup cache remembering ids for `ttl_ms` milliseconds.
+    pub fn new(ttl_ms: u64) -> Self {
+        Self {
+            ttl_ms,
+            seen_at: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Returns true if this envelope id was already seen, recording it otherwise.
+    pub fn seen(&mut self, envelope_id: &str) -> bool {
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        self.seen_at_time(envelope_id, now_ms)
+    }
+
+    /// Same as [`MessageDedup::seen`] with an explicit current time.
+    pub fn seen_at_time(&mut self, envelope_id: &str, now_ms: u64) -> bool {
+        self.expire(now_ms);
+        if self.seen_at.contains_key(envelope_id) {
+            return true;
+        }
+        self.seen_at.insert(envelope_id.to_string(), now_ms);
+        self.order.push_back((envelope_id.to_string(), now_ms));
+        false
+    }
+
+    /// Number of ids currently remembered.
+    pub fn len(&self) -> usize {
+        self.seen_at.len()
+    }
+
+    /// Returns true if no ids are remembered.
+    pub fn is_empty(&self) -> bool {
+        self.seen_at.is_empty()
+    }
+
+    fn expire(&mut self, now_ms: u64) {
+        while let Some((id, at)) = self.order.front() {
+            if now_ms.saturating_sub(*at) < self.ttl_ms {
+                break;
+            }
+            self.seen_at.remove(id);
+            self.order.pop_front();
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_ghost_update_serialization() {
+        let msg = AtlasMessage::GhostUpdate {
+            ghosts: vec![GhostEntity {
+                player_id: PlayerId::from("p1"),
+                position: WorldCoordinate::new(1005.0, 0.0, 20.0),
+                velocity: WorldCoordinate::new(-1.0, 0.0, 0.0),
+                authoritative_server: ServerId::from("horizon-2"),
+            }],
+        };
+        let json = serde_json::to_string(&msg).unwrap();
+        match serde_json::from_str::<AtlasMessage>(&json).unwrap() {
+            AtlasMessage::GhostUpdate { ghosts } => {
+                assert_eq!(ghosts.len(), 1);
+                assert_eq!(ghosts[0].authoritative_server.as_str(), "horizon-2");
+                assert_eq!(ghosts[0].position.x, 1005.0);
+            }
+            other => panic!("Unexpected message: {:?}", other),
+        }
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn test_envelope_compression_threshold() {
+        let small = Envelope::new("atlas", "horizon-1", Ack::success("msg-1"));
+        let bytes = small.to_bytes_compressed(1024).unwrap();
+        assert_eq!(bytes[0], ENVELOPE_FLAG_PLAIN);
+        let restored = Envelope::<Ack>::from_bytes_compressed(&bytes, 1024).unwrap();
+        assert_eq!(restored.message.message_id, "msg-1");
+        assert!(matches!(
+            Envelope::<Ack>::from_bytes_compressed(&bytes, 16),
+            Err(DecodeError::TooLarge { max: 16, .. })
+        ));
+
+        let large = Envelope::new("atlas", "horizon-1", Ack::failure("msg-2", "x".repeat(4096)));
+        let bytes = large.to_bytes_compressed(1024).unwrap();
+        assert_eq!(bytes[0], ENVELOPE_FLAG_DEFLATE);
+        assert!(bytes.len() < serde_json::to_vec(&large).unwrap().len());
+        let restored = Envelope::<Ack>::from_bytes_compressed(&bytes, 8192).unwrap();
+        assert_eq!(restored.id, large.id);
+        assert_eq!(restored.message.error.unwrap().len(), 4096);
+        // The limit applies to the inflated size, not the compressed bytes.
+        assert!(bytes.len() < 1024);
+        assert!(matches!(
+            Envelope::<Ack>::from_bytes_compressed(&bytes, 1024),
+            Err(DecodeError::DecompressedTooLarge { max: 1024 })
+        ));
+
+        assert!(matches!(
+            Envelope::<Ack>::from_bytes_compressed(&[7, b'{'], 1024),
+            Err(DecodeError::UnknownFormat(7))
+        ));
+    }
+
+    #[test]
+    fn test_capacity_warning_serialization() {
+        let msg = HorizonMessage::CapacityWarning {
+            server_id: ServerId::from("horizon-1"),
+            current: 92,
+            capacity: 100,
+            threshold: 0.9,
+        };
+        let json = serde_json::to_value(&msg).unwrap();
+        assert_eq!(json["type"], "CapacityWarning");
+        assert_eq!(json["payload"]["current"], 92);
+
+        match serde_json::from_value::<HorizonMessage>(json).unwrap() {
+            HorizonMessage::CapacityWarning { server_id, capacity, .. } => {
+                assert_eq!(server_id.as_str(), "horizon-1");
+                assert_eq!(capacity, 100);
+            }
+            other => panic!("Unexpected message: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_message_ordering() {
+        let position = HorizonMessage::PlayerPositionUpdate {
+            player_id: PlayerId::from("p1"),
+            position: WorldCoordinate::zero(),
+            velocity: WorldCoordinate::zero(),
+        };
+        let heartbeat = HorizonMessage::Heartbeat(ServerHeartbeat::new(
+            ServerId::new(),
+            crate::server::ServerStatus::Running,
+            0,
+            100,
+        ));
+        let shutdown = AtlasMessage::PrepareShutdown { deadline_secs: 30 };
+        let stats = AtlasToMaestroMessage::GetServerStats { instance_id: "i-1".to_string() };
+        let split = MaestroMessage::RegionSplit {
+            parent: RegionCoordinate::center(),
+            children: RegionCoordinate::center().subdivide(2),
+        };
+
+        assert_eq!(position.ordering(), MessageOrdering::PerPlayer);
+        assert_eq!(heartbeat.ordering(), MessageOrdering::None);
+        assert_eq!(shutdown.ordering(), MessageOrdering::Strict);
+        assert_eq!(stats.ordering(), MessageOrdering::None);
+        assert_eq!(split.ordering(), MessageOrdering::Strict);
+        assert_eq!(AtlasMessage::GhostUpdate { ghosts: Vec::new() }.ordering(), MessageOrdering::Strict);
+
+        for (kind, json) in [
+            (position.kind(), serde_json::to_value(&position).unwrap()),
+            (heartbeat.kind(), serde_json::to_value(&heartbeat).unwrap()),
+            (shutdown.kind(), serde_json::to_value(&shutdown).unwrap()),
+            (split.kind(), serde_json::to_value(&split).unwrap()),
+        ] {
+            assert_eq!(json["type"], kind);
+        }
+    }
+
+    #[test]
+    fn test_envelope_clock_skew() {
+        let mut envelope = Envelope::new("horizon-1", "atlas", Ack::success("msg-1"));
+        envelope.timestamp_ms = 1_000_000;
+
+        assert_eq!(envelope.clock_skew_ms(1_000_150), -150);
+        assert!(!envelope.is_skewed(1_000_150, 500));
+
+        envelope.timestamp_ms = 1_030_000;
+        assert_eq!(envelope.clock_skew_ms(1_000_000), 30_000);
+        assert!(envelope.is_skewed(1_000_000, 5_000));
+    }
+
+    #[test]
+    fn test_region_split_and_merge_messages() {
+        let parent = RegionCoordinate::new(0, 0, 1);
+        let split = MaestroMessage::RegionSplit {
+            parent,
+            children: parent.subdivide(2),
+        };
+        assert!(split.validate().is_ok());
+
+        let json = serde_json::to_string(&split).unwrap();
+        assert!(json.contains("\"type\":\"RegionSplit\""));
+        match serde_json::from_str::<MaestroMessage>(&json).unwrap() {
+            MaestroMessage::RegionSplit { children, .. } => assert_eq!(children.len(), 8),
+            other => panic!("Unexpected message: {:?}", other),
+        }
+
+        let mut children = parent.subdivide(2);
+        let missing = children.pop().unwrap();
+        let merged = MaestroMessage::RegionMerged { children, parent };
+        assert_eq!(merged.validate(), Err(TilingError::Gap(missing)));
+    }
+
+    #[test]
+    fn test_pending_acks_priority_deadlines() {
+        assert_eq!(ack_timeout(MessagePriority::Critical, 1000), 250);
+        assert_eq!(ack_timeout(MessagePriority::Low, 1000), 2000);
+
+        let mut acks = PendingAcks::new(1000);
+        acks.track("normal", MessagePriority::Normal, 0);
+        acks.track("critical", MessagePriority::Critical, 0);
+        acks.track("low", MessagePriority::Low, 0);
+
+        assert!(acks.timed_out(249).is_empty());
+        assert_eq!(acks.timed_out(250), vec!["critical"]);
+        assert_eq!(acks.timed_out(1000), vec!["critical", "normal"]);
+
+        assert!(acks.acknowledge(&Ack::success("critical")));
+        assert!(!acks.acknowledge(&Ack::success("critical")));
+        assert_eq!(acks.timed_out(5000), vec!["normal", "low"]);
+        assert_eq!(acks.len(), 2);
+    }
+
     #[test]
     fn test_horizon_message_serialization() {
         let msg = HorizonMessage::PlayerConnected {
@@ -257,6 +1055,255 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_interest_config_serialization() {
+        let msg = AtlasMessage::InterestConfig(InterestConfig {
+            default_view_distance: 150.0,
+            max_view_distance: 400.0,
+            cell_size: 50.0,
+        });
+        let json = serde_json::to_value(&msg).unwrap();
+        assert_eq!(json["type"], "InterestConfig");
+        assert_eq!(json["payload"]["max_view_distance"], 400.0);
+        match serde_json::from_value::<AtlasMessage>(json).unwrap() {
+            AtlasMessage::InterestConfig(config) => assert!(config.validate().is_ok()),
+            _ => panic!("Wrong message type"),
+        }
+    }
+
+    #[test]
+    fn test_player_list_pagination() {
+        let players: Vec<PlayerInfo> = (0..5)
+            .map(|i| PlayerInfo::new(PlayerId::from(format!("p{}", i)), format!("Player{}", i)))
+            .collect();
+        let pages: Vec<AtlasMessage> = player_list_pages(&players, 2).collect();
+        assert_eq!(pages.len(), 3);
+
+        let json = serde_json::to_string(&pages[2]).unwrap();
+        match serde_json::from_str::<AtlasMessage>(&json).unwrap() {
+            AtlasMessage::PlayerListPage { page, players, has_more, .. } => {
+                assert_eq!(page, 2);
+                assert_eq!(players.len(), 1);
+                assert!(!has_more);
+            }
+            _ => panic!("Wrong message type"),
+        }
+        assert!(matches!(pages[0], AtlasMessage::PlayerListPage { has_more: true, .. }));
+        assert_eq!(player_list_pages(&[], 10).count(), 1);
+
+        let request = HorizonMessage::PlayerListRequest { cursor: None, page_size: 50 };
+        let json = serde_json::to_string(&request).unwrap();
+        assert!(matches!(
+            serde_json::from_str::<HorizonMessage>(&json).unwrap(),
+            HorizonMessage::PlayerListRequest { cursor: None, page_size: 50 }
+        ));
+    }
+
+    #[test]
+    fn test_player_list_cursor_survives_churn() {
+        let player = |i: u32| PlayerInfo::new(PlayerId::from(format!("p{}", i)), format!("Player{}", i));
+        let mut players: Vec<PlayerInfo> = (0..6).map(player).collect();
+        let next_cursor = |page: &AtlasMessage| match page {
+            AtlasMessage::PlayerListPage { next_cursor, .. } => next_cursor.clone(),
+            _ => panic!("Wrong message type"),
+        };
+        let ids = |page: &AtlasMessage| match page {
+            AtlasMessage::PlayerListPage { players, .. } => {
+                players.iter().map(|p| p.id.as_str().to_string()).collect::<Vec<_>>()
+            }
+            _ => panic!("Wrong message type"),
+        };
+
+        let first = player_list_page(&players, None, 2);
+        assert_eq!(ids(&first), vec!["p0", "p1"]);
+        let cursor = next_cursor(&first).unwrap();
+
+        // A player ahead of the cursor leaves; nobody is skipped or repeated.
+        players.remove(0);
+        let second = player_list_page(&players, Some(&cursor), 2);
+        assert_eq!(ids(&second), vec!["p2", "p3"]);
+        assert!(matches!(second, AtlasMessage::PlayerListPage { page: 1, has_more: true, .. }));
+
+        let last = player_list_page(&players, next_cursor(&second).as_deref(), 2);
+        assert_eq!(ids(&last), vec!["p4", "p5"]);
+        assert!(matches!(last, AtlasMessage::PlayerListPage { page: 2, has_more: false, next_cursor: None, .. }));
+        assert_eq!(ids(&player_list_page(&players, Some("garbage"), 2)), vec!["p1", "p2"]);
+    }
+
+    #[test]
+    fn test_transfer_declined_serialization() {
+        let msg = HorizonMessage::TransferDeclined {
+            token_id: "txfr-2".to_string(),
+            player_id: PlayerId::from("p1"),
+            reason: TransferError::TransferRejected("server full".to_string()),
+        };
+        assert_eq!(msg.kind(), "TransferDeclined");
+        let json = serde_json::to_string(&msg).unwrap();
+        match serde_json::from_str::<HorizonMessage>(&json).unwrap() {
+            HorizonMessage::TransferDeclined { token_id, reason, .. } => {
+                assert_eq!(token_id, "txfr-2");
+                assert!(matches!(reason, TransferError::TransferRejected(ref r) if r == "server full"));
+                assert!(reason.try_alternate_target());
+                assert!(!reason.is_retryable());
+            }
+            _ => panic!("Wrong message type"),
+        }
+    }
+
+    #[test]
+    fn test_transfer_rollback_serialization() {
+        let msg = HorizonMessage::TransferRollback {
+            token_id: "txfr-1".to_string(),
+            player_id: PlayerId::from("p1"),
+            reason: "player never connected to target".to_string(),
+        };
+        let json = serde_json::to_string(&msg).unwrap();
+        match serde_json::from_str::<HorizonMessage>(&json).unwrap() {
+            HorizonMessage::TransferRollback { token_id, player_id, .. } => {
+                assert_eq!(token_id, "txfr-1");
+                assert_eq!(player_id.as_str(), "p1");
+            }
+            _ => panic!("Wrong message type"),
+        }
+    }
+
+    #[test]
+    fn test_region_load_report_serialization() {
+        let msg = AtlasToMaestroMessage::RegionLoadReport {
+            regions: vec![RegionLoad {
+                region: RegionCoordinate::new(0, 1, 0),
+                server_id: None,
+                players: 0,
+                capacity: 0,
+                load: crate::server::Load::ZERO,
+            }],
+        };
+        let json = serde_json::to_string(&msg).unwrap();
+        match serde_json::from_str::<AtlasToMaestroMessage>(&json).unwrap() {
+            AtlasToMaestroMessage::RegionLoadReport { regions } => {
+                assert_eq!(regions[0].region, RegionCoordinate::new(0, 1, 0));
+                assert!(regions[0].server_id.is_none());
+            }
+            _ => panic!("Wrong message type"),
+        }
+    }
+
+    #[test]
+    fn test_shutdown_reason_serialization() {
+        let reasons = [
+            ShutdownReason::Planned,
+            ShutdownReason::Crashed,
+            ShutdownReason::OutOfMemory,
+            ShutdownReason::Migrated,
+            ShutdownReason::Unknown,
+        ];
+        for reason in reasons {
+            let msg = HorizonMessage::Shutdown {
+                server_id: ServerId::from("horizon-1"),
+                player_count: 0,
+                reason,
+            };
+            let json = serde_json::to_string(&msg).unwrap();
+            match serde_json::from_str::<HorizonMessage>(&json).unwrap() {
+                HorizonMessage::Shutdown { reason: restored, .. } => assert_eq!(restored, reason),
+                _ => panic!("Wrong message type"),
+            }
+
+            let msg = MaestroMessage::ServerStopped {
+                instance_id: "container-1".to_string(),
+                exit_code: Some(137),
+                reason,
+            };
+            let json = serde_json::to_string(&msg).unwrap();
+            match serde_json::from_str::<MaestroMessage>(&json).unwrap() {
+                MaestroMessage::ServerStopped { reason: restored, .. } => assert_eq!(restored, reason),
+                _ => panic!("Wrong message type"),
+            }
+        }
+
+        // Messages from older senders without a reason still parse.
+        let legacy = r#"{"type":"Shutdown","payload":{"server_id":"horizon-1","player_count":3}}"#;
+        match serde_json::from_str::<HorizonMessage>(legacy).unwrap() {
+            HorizonMessage::Shutdown { reason, .. } => assert_eq!(reason, ShutdownReason::Unknown),
+            _ => panic!("Wrong message type"),
+        }
+    }
+
+    #[test]
+    fn test_region_ownership_messages_serialization() {
+        let msg = AtlasMessage::TransferRegionOwnership {
+            region: RegionCoordinate::new(1, 0, -2),
+            to: ServerId::from("horizon-2"),
+        };
+        let json = serde_json::to_string(&msg).unwrap();
+        match serde_json::from_str::<AtlasMessage>(&json).unwrap() {
+            AtlasMessage::TransferRegionOwnership { region, to } => {
+                assert_eq!(region, RegionCoordinate::new(1, 0, -2));
+                assert_eq!(to.as_str(), "horizon-2");
+            }
+            _ => panic!("Wrong message type"),
+        }
+
+        let msg = HorizonMessage::RegionOwnershipReleased {
+            region: RegionCoordinate::new(1, 0, -2),
+            player_count: 12,
+        };
+        let json = serde_json::to_string(&msg).unwrap();
+        match serde_json::from_str::<HorizonMessage>(&json).unwrap() {
+            HorizonMessage::RegionOwnershipReleased { player_count, .. } => assert_eq!(player_count, 12),
+            _ => panic!("Wrong message type"),
+        }
+    }
+
+    #[test]
+    fn test_message_dedup() {
+        let mut dedup = MessageDedup::new(1_000);
+        assert!(!dedup.seen_at_time("msg-1", 0));
+        assert!(dedup.seen_at_time("msg-1", 500));
+        assert!(!dedup.seen_at_time("msg-2", 600));
+
+        // msg-1 expires, msg-2 is still remembered
+        assert!(!dedup.seen_at_time("msg-1", 1_200));
+        assert!(dedup.seen_at_time("msg-2", 1_200));
+        assert_eq!(dedup.len(), 2);
+    }
+
+    #[test]
+    fn test_size_histogram_percentile() {
+        let mut hist = SizeHistogram::new();
+        for i in 0..9 {
+            Envelope::new("horizon-1", "atlas", Ack::success(format!("msg-{}", i)))
+                .record_size(&mut hist)
+                .unwrap();
+        }
+        let large = Envelope::new("horizon-1", "atlas", Ack::failure("msg-9", "x".repeat(2_000)));
+        let large_size = large.record_size(&mut hist).unwrap();
+
+        assert_eq!(hist.count(), 10);
+        let p90 = hist.percentile(90.0).unwrap();
+        assert!(p90 <= 256, "p90 should land in a small-message bucket, got {}", p90);
+        assert_eq!(hist.percentile(100.0), Some(large_size));
+        assert_eq!(SizeHistogram::new().percentile(90.0), None);
+    }
+
+    #[test]
+    fn test_envelope_causation_chain() {
+        let request = Envelope::new("atlas", "maestro", AtlasToMaestroMessage::ScaleCluster { target_count: 3 });
+        let scaled = Envelope::new(
+            "maestro",
+            "atlas",
+            MaestroMessage::ClusterScaled { current_count: 3, target_count: 3 },
+        )
+        .caused_by(&request);
+        let ack = Envelope::new("atlas", "horizon-1", Ack::success(&scaled.id)).caused_by(&scaled);
+
+        assert_eq!(request.causation_id, None);
+        assert_eq!(scaled.causation_id.as_deref(), Some(request.id.as_str()));
+        assert_eq!(ack.causation_id.as_deref(), Some(scaled.id.as_str()));
+        assert_eq!(scaled.correlation_id.as_deref(), Some(request.id.as_str()));
+        assert_eq!(ack.correlation_id.as_deref(), Some(request.id.as_str()));
+    }
+
     #[test]
     fn test_envelope_creation() {
         let msg = Ack::success("test-123");
@@ -6,6 +6,7 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 
+use crate::error::{ErrorCategory, ErrorCode};
 use crate::spatial::WorldCoordinate;
 use crate::server::ServerId;
 
@@ -49,6 +50,33 @@ impl From<&str> for PlayerId {
     }
 }
 
+/// Namespace used to derive UUID v5 player ids from legacy string ids.
+pub const LEGACY_PLAYER_ID_NAMESPACE: uuid::Uuid = uuid::Uuid::from_u128(0x6f1c_2a4e_9b3d_4c8a_a5e7_1d0f_3b2c_8e94);
+
+/// Serde `with` module for [`PlayerId`] fields that may hold legacy ids.
+///
+/// Ids that parse as a UUID are kept in canonical hyphenated form. Anything
+/// else (usernames, numeric ids from older persistence) is mapped to a UUID v5
+/// derived from the raw string in [`LEGACY_PLAYER_ID_NAMESPACE`], so the same
+/// legacy id always loads as the same player. Serialization is unchanged. Use
+/// with `#[serde(with = "player_id_lenient")]`.
+pub mod player_id_lenient {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{PlayerId, LEGACY_PLAYER_ID_NAMESPACE};
+
+    pub fn serialize<S: Serializer>(id: &PlayerId, serializer: S) -> Result<S::Ok, S::Error> {
+        id.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<PlayerId, D::Error> {
+        let raw = String::deserialize(deserializer)?;
+        let uuid = uuid::Uuid::parse_str(&raw)
+            .unwrap_or_else(|_| uuid::Uuid::new_v5(&LEGACY_PLAYER_ID_NAMESPACE, raw.as_bytes()));
+        Ok(PlayerId(uuid.to_string()))
+    }
+}
+
 /// Authentication status of a player.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
@@ -121,6 +149,98 @@ impl PlayerInfo {
     }
 }
 
+/// Key prefix marking player data as private to the server that holds it.
+///
+/// Entries in `PlayerState::custom_data` (and top-level keys of an object
+/// `persistent_data`) whose key starts with this prefix are local: they are
+/// dropped by [`PlayerState::transferable`] and never leave the server.
+pub const LOCAL_KEY_PREFIX: &str = "_local";
+
+/// Set of [`PlayerState`] fields a game sends on transfer.
+///
+/// Combine flags with `|`; see [`PlayerState::project`]. Serializes as its
+/// bits; deserialization rejects unknown bits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "u8", into = "u8")]
+pub struct TransferFields(u8);
+
+/// Error returned for [`TransferFields`] bits that name no field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("Unknown transfer field bits: {0:#010b}")]
+pub struct UnknownTransferFields(pub u8);
+
+impl TransferFields {
+    /// Player position (`info.last_position`)
+    pub const POSITION: TransferFields = TransferFields(1 << 0);
+    /// Velocity vector
+    pub const VELOCITY: TransferFields = TransferFields(1 << 1);
+    /// Player health
+    pub const HEALTH: TransferFields = TransferFields(1 << 2);
+    /// Custom game-specific data
+    pub const CUSTOM: TransferFields = TransferFields(1 << 3);
+    /// Inventory or other persistent data
+    pub const PERSISTENT: TransferFields = TransferFields(1 << 4);
+    /// No optional fields
+    pub const NONE: TransferFields = TransferFields(0);
+    /// Every field
+    pub const ALL: TransferFields = TransferFields(0b1_1111);
+
+    /// Returns true if every flag in `other` is set.
+    pub fn contains(self, other: TransferFields) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Raw flag bits.
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+}
+
+impl TryFrom<u8> for TransferFields {
+    type Error = UnknownTransferFields;
+
+    fn try_from(bits: u8) -> Result<Self, Self::Error> {
+        if bits & !Self::ALL.0 != 0 {
+            return Err(UnknownTransferFields(bits & !Self::ALL.0));
+        }
+        Ok(TransferFields(bits))
+    }
+}
+
+impl From<TransferFields> for u8 {
+    fn from(fields: TransferFields) -> u8 {
+        fields.0
+    }
+}
+
+impl Default for TransferFields {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+impl std::ops::BitOr for TransferFields {
+    type Output = TransferFields;
+
+    fn bitor(self, rhs: TransferFields) -> TransferFields {
+        TransferFields(self.0 | rhs.0)
+    }
+}
+
+impl std::ops::BitOrAssign for TransferFields {
+    fn bitor_assign(&mut self, rhs: TransferFields) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl std::ops::BitAnd for TransferFields {
+    type Output = TransferFields;
+
+    fn bitand(self, rhs: TransferFields) -> TransferFields {
+        TransferFields(self.0 & rhs.0)
+    }
+}
+
 /// Player state that can be serialized for transfer between servers.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlayerState {
@@ -136,9 +256,19 @@ pub struct PlayerState {
     /// Inventory or other persistent data
     #[serde(default)]
     pub persistent_data: serde_json::Value,
+    /// Fields this state actually carries (see [`PlayerState::project`])
+    #[serde(default)]
+    pub fields: TransferFields,
 }
 
 impl PlayerState {
+    /// Starts building a player state from player info.
+    pub fn builder(info: PlayerInfo) -> PlayerStateBuilder {
+        PlayerStateBuilder {
+            state: Self::new(info),
+        }
+    }
+
     /// Creates a new player state from player info.
     pub fn new(info: PlayerInfo) -> Self {
         Self {
@@ -147,7 +277,82 @@ impl PlayerState {
             health: 1.0,
             custom_data: std::collections::HashMap::new(),
             persistent_data: serde_json::Value::Null,
+            fields: TransferFields::ALL,
+        }
+    }
+
+    /// Returns a copy safe to send to another server, with local data removed.
+    ///
+    /// See [`LOCAL_KEY_PREFIX`] for the naming convention.
+    pub fn transferable(&self) -> PlayerState {
+        let mut state = self.clone();
+        state.custom_data.retain(|key, _| !key.starts_with(LOCAL_KEY_PREFIX));
+        if let serde_json::Value::Object(map) = &mut state.persistent_data {
+            map.retain(|key, _| !key.starts_with(LOCAL_KEY_PREFIX));
+        }
+        state
+    }
+
+    /// Returns a copy containing only the given fields.
+    ///
+    /// Omitted fields are reset to the values [`PlayerState::new`] uses (zero
+    /// vectors, full health, empty data), so they cost almost nothing on the
+    /// wire, and are cleared from `fields` so the receiver knows not to apply
+    /// them (see [`PlayerState::fill_omitted`]). Player info other than the
+    /// position is always kept.
+    pub fn project(&self, fields: TransferFields) -> PlayerState {
+        let mut state = self.clone();
+        state.fields = self.fields & fields;
+        if !fields.contains(TransferFields::POSITION) {
+            state.info.last_position = WorldCoordinate::zero();
+        }
+        if !fields.contains(TransferFields::VELOCITY) {
+            state.velocity = WorldCoordinate::zero();
+        }
+        if !fields.contains(TransferFields::HEALTH) {
+            state.health = 1.0;
+        }
+        if !fields.contains(TransferFields::CUSTOM) {
+            state.custom_data.clear();
+        }
+        if !fields.contains(TransferFields::PERSISTENT) {
+            state.persistent_data = serde_json::Value::Null;
         }
+        state
+    }
+
+    /// Copies every field this state does not carry from `current`.
+    ///
+    /// A receiving server calls this with the state it already holds so that
+    /// fields left out of a projection keep their values instead of resetting.
+    pub fn fill_omitted(&mut self, current: &PlayerState) {
+        if !self.fields.contains(TransferFields::POSITION) {
+            self.info.last_position = current.info.last_position;
+        }
+        if !self.fields.contains(TransferFields::VELOCITY) {
+            self.velocity = current.velocity;
+        }
+        if !self.fields.contains(TransferFields::HEALTH) {
+            self.health = current.health;
+        }
+        if !self.fields.contains(TransferFields::CUSTOM) {
+            self.custom_data = current.custom_data.clone();
+        }
+        if !self.fields.contains(TransferFields::PERSISTENT) {
+            self.persistent_data = current.persistent_data.clone();
+        }
+        self.fields |= current.fields;
+    }
+
+    /// Runs a game-specific validator over `persistent_data`.
+    ///
+    /// The crate does not know each game's schema; servers call this with their
+    /// own check before trusting state received in a transfer.
+    pub fn validate_persistent<F>(&self, validator: F) -> Result<(), PlayerStateError>
+    where
+        F: Fn(&serde_json::Value) -> Result<(), String>,
+    {
+        validator(&self.persistent_data).map_err(PlayerStateError::InvalidPersistentData)
     }
 
     /// Serializes the player state to JSON.
@@ -161,6 +366,78 @@ impl PlayerState {
     }
 }
 
+/// Errors from building a [`PlayerState`].
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum PlayerStateError {
+    /// Velocity has a NaN or infinite component
+    #[error("Velocity must be finite")]
+    NonFiniteVelocity,
+
+    /// Last known position has a NaN or infinite component
+    #[error("Position must be finite")]
+    NonFinitePosition,
+
+    /// Health is NaN or infinite
+    #[error("Health must be finite, got {0}")]
+    NonFiniteHealth(f32),
+
+    /// Persistent data was rejected by a game-supplied validator
+    #[error("Invalid persistent data: {0}")]
+    InvalidPersistentData(String),
+}
+
+/// Fluent builder for [`PlayerState`], created by [`PlayerState::builder`].
+#[derive(Debug, Clone)]
+pub struct PlayerStateBuilder {
+    state: PlayerState,
+}
+
+impl PlayerStateBuilder {
+    /// Sets the velocity.
+    pub fn velocity(mut self, velocity: WorldCoordinate) -> Self {
+        self.state.velocity = velocity;
+        self
+    }
+
+    /// Sets the health; clamped to 0.0 to 1.0 on build.
+    pub fn health(mut self, health: f32) -> Self {
+        self.state.health = health;
+        self
+    }
+
+    /// Adds a custom data entry.
+    pub fn custom(mut self, key: impl Into<String>, value: impl Into<serde_json::Value>) -> Self {
+        self.state.custom_data.insert(key.into(), value.into());
+        self
+    }
+
+    /// Sets the persistent data.
+    pub fn persistent(mut self, value: serde_json::Value) -> Self {
+        self.state.persistent_data = value;
+        self
+    }
+
+    /// Validates and returns the player state.
+    ///
+    /// Rejects non-finite velocity, position or health, and clamps health to
+    /// the 0.0 to 1.0 range.
+    pub fn build(self) -> Result<PlayerState, PlayerStateError> {
+        let mut state = self.state;
+        let finite = |c: &WorldCoordinate| c.x.is_finite() && c.y.is_finite() && c.z.is_finite();
+        if !finite(&state.velocity) {
+            return Err(PlayerStateError::NonFiniteVelocity);
+        }
+        if !finite(&state.info.last_position) {
+            return Err(PlayerStateError::NonFinitePosition);
+        }
+        if !state.health.is_finite() {
+            return Err(PlayerStateError::NonFiniteHealth(state.health));
+        }
+        state.health = state.health.clamp(0.0, 1.0);
+        Ok(state)
+    }
+}
+
 /// Movement data for player position prediction.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Default)]
 pub struct MovementData {
@@ -200,13 +477,243 @@ pub enum DisconnectReason {
     /// Player is being transferred
     Transfer { target_server: ServerId },
     /// An error occurred
-    Error { message: String },
+    Error {
+        message: String,
+        #[serde(default)]
+        error_code: Option<ErrorCode>,
+    },
+}
+
+impl DisconnectReason {
+    /// Category of the error behind an `Error` disconnect, if it carries a code.
+    pub fn error_category(&self) -> Option<ErrorCategory> {
+        match self {
+            Self::Error { error_code, .. } => error_code.map(|code| code.category()),
+            _ => None,
+        }
+    }
+}
+
+/// How long a player may reconnect and resume their session after a disconnect.
+///
+/// Involuntary disconnects (timeouts, network errors, transfers, shutdowns)
+/// get a grace window; deliberate ones (kicks, the player quitting) and
+/// non-network errors get none.
+pub fn reconnect_grace_secs(reason: &DisconnectReason) -> Option<u32> {
+    match reason {
+        DisconnectReason::Timeout => Some(60),
+        DisconnectReason::Transfer { .. } => Some(30),
+        DisconnectReason::ServerShutdown => Some(120),
+        DisconnectReason::Error { .. } if reason.error_category() == Some(ErrorCategory::Network) => Some(60),
+        DisconnectReason::Error { .. } | DisconnectReason::Kicked { .. } | DisconnectReason::ClientDisconnect => None,
+    }
+}
+
+/// Token letting a disconnected player resume their session within a grace window.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReconnectToken {
+    /// Random token identifier presented by the client
+    pub token_id: String,
+    /// Player the token belongs to
+    pub player_id: PlayerId,
+    /// Server holding the player's session
+    pub server_id: ServerId,
+    /// When the token stops being accepted
+    pub expires_at: DateTime<Utc>,
+}
+
+impl ReconnectToken {
+    /// Issues a token for a disconnect, if the reason allows reconnecting.
+    ///
+    /// The expiry is `now` plus [`reconnect_grace_secs`] for the reason.
+    pub fn issue(
+        player_id: PlayerId,
+        server_id: ServerId,
+        reason: &DisconnectReason,
+        now: DateTime<Utc>,
+    ) -> Option<Self> {
+        let grace = reconnect_grace_secs(reason)?;
+        Some(Self {
+            token_id: uuid::Uuid::new_v4().to_string(),
+            player_id,
+            server_id,
+            expires_at: now + chrono::Duration::seconds(grace as i64),
+        })
+    }
+
+    /// Returns true if the token is still accepted at `now`.
+    pub fn is_valid_at(&self, now: DateTime<Utc>) -> bool {
+        now < self.expires_at
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_project_position_and_velocity() {
+        let mut info = PlayerInfo::new(PlayerId::from("p1"), "Alice".to_string());
+        info.last_position = WorldCoordinate::new(10.0, 0.0, -4.0);
+        let state = PlayerState::builder(info)
+            .velocity(WorldCoordinate::new(1.0, 2.0, 0.0))
+            .health(0.4)
+            .custom("mount", serde_json::json!("horse"))
+            .persistent(serde_json::json!({"gold": 12}))
+            .build()
+            .unwrap();
+
+        let projected = state.project(TransferFields::POSITION | TransferFields::VELOCITY);
+        assert_eq!(projected.info.last_position, WorldCoordinate::new(10.0, 0.0, -4.0));
+        assert_eq!(projected.velocity, WorldCoordinate::new(1.0, 2.0, 0.0));
+        assert_eq!(projected.health, 1.0);
+        assert!(projected.custom_data.is_empty());
+        assert_eq!(projected.persistent_data, serde_json::Value::Null);
+        assert!(projected.to_json().unwrap().len() < state.to_json().unwrap().len());
+
+        let full = state.project(TransferFields::default());
+        assert_eq!(full.health, 0.4);
+        assert_eq!(full.custom_data.len(), 1);
+
+        let mut received = PlayerState::from_json(&projected.to_json().unwrap()).unwrap();
+        assert_eq!(received.fields, TransferFields::POSITION | TransferFields::VELOCITY);
+        assert!(!received.fields.contains(TransferFields::HEALTH));
+        received.fill_omitted(&state);
+        assert_eq!(received.health, 0.4);
+        assert_eq!(received.persistent_data, serde_json::json!({"gold": 12}));
+        assert_eq!(received.fields, TransferFields::ALL);
+    }
+
+    #[test]
+    fn test_transfer_fields_serde() {
+        let fields = TransferFields::HEALTH | TransferFields::CUSTOM;
+        assert_eq!(serde_json::to_string(&fields).unwrap(), "12");
+        assert_eq!(serde_json::from_str::<TransferFields>("12").unwrap(), fields);
+        assert!(serde_json::from_str::<TransferFields>("32").is_err());
+        assert_eq!(TransferFields::try_from(0xff), Err(UnknownTransferFields(0b1110_0000)));
+
+        let state = PlayerState::new(PlayerInfo::new(PlayerId::from("p1"), "Alice".to_string()));
+        let mut legacy = serde_json::to_value(state).unwrap();
+        legacy.as_object_mut().unwrap().remove("fields");
+        assert_eq!(serde_json::from_value::<PlayerState>(legacy).unwrap().fields, TransferFields::ALL);
+    }
+
+    #[test]
+    fn test_reconnect_grace_by_reason() {
+        let network_error = DisconnectReason::Error {
+            message: "connection reset".to_string(),
+            error_code: Some(ErrorCode::ConnectionLost),
+        };
+        let server_error = DisconnectReason::Error {
+            message: "panic".to_string(),
+            error_code: Some(ErrorCode::InternalError),
+        };
+        let cases = [
+            (DisconnectReason::Timeout, Some(60)),
+            (DisconnectReason::Transfer { target_server: ServerId::from("horizon-2") }, Some(30)),
+            (DisconnectReason::ServerShutdown, Some(120)),
+            (network_error, Some(60)),
+            (server_error, None),
+            (DisconnectReason::Kicked { reason: "afk".to_string() }, None),
+            (DisconnectReason::ClientDisconnect, None),
+        ];
+        for (reason, expected) in &cases {
+            assert_eq!(reconnect_grace_secs(reason), *expected, "{:?}", reason);
+        }
+    }
+
+    #[test]
+    fn test_reconnect_token_issue() {
+        let now = Utc::now();
+        let token = ReconnectToken::issue(
+            PlayerId::from("p1"),
+            ServerId::from("horizon-1"),
+            &DisconnectReason::Timeout,
+            now,
+        )
+        .unwrap();
+        assert!(token.is_valid_at(now + chrono::Duration::seconds(59)));
+        assert!(!token.is_valid_at(now + chrono::Duration::seconds(60)));
+
+        let kicked = DisconnectReason::Kicked { reason: "cheating".to_string() };
+        assert!(ReconnectToken::issue(PlayerId::from("p1"), ServerId::from("horizon-1"), &kicked, now).is_none());
+    }
+
+    #[test]
+    fn test_player_id_lenient() {
+        #[derive(Serialize, Deserialize)]
+        struct Record {
+            #[serde(with = "player_id_lenient")]
+            player: PlayerId,
+        }
+
+        let uuid = "3f2b1c4d-5e6f-4a7b-8c9d-0e1f2a3b4c5d";
+        let record: Record = serde_json::from_value(serde_json::json!({"player": uuid})).unwrap();
+        assert_eq!(record.player.as_str(), uuid);
+
+        let legacy: Record = serde_json::from_value(serde_json::json!({"player": "steve_1987"})).unwrap();
+        let again: Record = serde_json::from_value(serde_json::json!({"player": "steve_1987"})).unwrap();
+        assert_eq!(legacy.player, again.player);
+        let parsed = uuid::Uuid::parse_str(legacy.player.as_str()).unwrap();
+        assert_eq!(parsed.get_version_num(), 5);
+        assert_eq!(serde_json::to_value(&legacy).unwrap()["player"], legacy.player.as_str());
+    }
+
+    #[test]
+    fn test_player_state_builder() {
+        let info = PlayerInfo::new(PlayerId::from("p1"), "TestPlayer".to_string());
+        let state = PlayerState::builder(info.clone())
+            .velocity(WorldCoordinate::new(1.0, 0.0, -2.0))
+            .health(1.7)
+            .custom("team", "red")
+            .custom("kills", 12)
+            .persistent(serde_json::json!({"inventory": ["sword"]}))
+            .build()
+            .unwrap();
+        assert_eq!(state.health, 1.0);
+        assert_eq!(state.velocity, WorldCoordinate::new(1.0, 0.0, -2.0));
+        assert_eq!(state.custom_data["team"], "red");
+        assert_eq!(state.custom_data["kills"], 12);
+        assert_eq!(state.persistent_data["inventory"][0], "sword");
+
+        assert_eq!(PlayerState::builder(info.clone()).health(-3.0).build().unwrap().health, 0.0);
+        assert_eq!(
+            PlayerState::builder(info.clone()).health(f32::NAN).build().unwrap_err().to_string(),
+            "Health must be finite, got NaN"
+        );
+        assert_eq!(
+            PlayerState::builder(info)
+                .velocity(WorldCoordinate::new(f64::INFINITY, 0.0, 0.0))
+                .build()
+                .unwrap_err(),
+            PlayerStateError::NonFiniteVelocity
+        );
+    }
+
+    #[test]
+    fn test_validate_persistent() {
+        let info = PlayerInfo::new(PlayerId::from("p1"), "TestPlayer".to_string());
+        let mut state = PlayerState::new(info);
+        state.persistent_data = serde_json::json!({"gold": 120});
+
+        let gold_is_number = |data: &serde_json::Value| {
+            if data["gold"].is_u64() {
+                Ok(())
+            } else {
+                Err("gold must be a non-negative integer".to_string())
+            }
+        };
+        assert!(state.validate_persistent(gold_is_number).is_ok());
+
+        state.persistent_data = serde_json::json!({"gold": "lots"});
+        assert_eq!(
+            state.validate_persistent(gold_is_number),
+            Err(PlayerStateError::InvalidPersistentData(
+                "gold must be a non-negative integer".to_string()
+            ))
+        );
+    }
+
     #[test]
     fn test_player_state_serialization() {
         let info = PlayerInfo::new(PlayerId::new(), "TestPlayer".to_string());
@@ -216,6 +723,36 @@ mod tests {
         assert_eq!(restored.info.name, "TestPlayer");
     }
 
+    #[test]
+    fn test_transferable_strips_local_keys() {
+        let mut state = PlayerState::new(PlayerInfo::new(PlayerId::new(), "TestPlayer".to_string()));
+        state.custom_data.insert("score".to_string(), serde_json::json!(10));
+        state.custom_data.insert("_local_anticheat".to_string(), serde_json::json!({"flags": 2}));
+        state.persistent_data = serde_json::json!({"inventory": [1, 2], "_local_cache": true});
+
+        let transferable = state.transferable();
+        assert!(transferable.custom_data.contains_key("score"));
+        assert!(!transferable.custom_data.contains_key("_local_anticheat"));
+        assert_eq!(transferable.persistent_data, serde_json::json!({"inventory": [1, 2]}));
+        assert!(state.custom_data.contains_key("_local_anticheat"));
+    }
+
+    #[test]
+    fn test_disconnect_error_code() {
+        let reason = DisconnectReason::Error {
+            message: "lost connection to client".to_string(),
+            error_code: Some(ErrorCode::ConnectionLost),
+        };
+        let json = serde_json::to_string(&reason).unwrap();
+        let restored: DisconnectReason = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.error_category(), Some(ErrorCategory::Network));
+
+        let legacy: DisconnectReason = serde_json::from_str(r#"{"error":{"message":"boom"}}"#).unwrap();
+        assert!(matches!(legacy, DisconnectReason::Error { error_code: None, .. }));
+        assert_eq!(legacy.error_category(), None);
+        assert_eq!(DisconnectReason::Timeout.error_category(), None);
+    }
+
     #[test]
     fn test_movement_prediction() {
         let movement = MovementData {
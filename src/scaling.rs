@@ -0,0 +1,592 @@
+//! Load balancing and scaling helpers.
+//!
+//! These types are used by Atlas and Maestro to decide when the cluster
+//! needs to be rebalanced or resized.
+
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{HashSet, VecDeque};
+
+use crate::grid::{InvalidRegionDims, RegionDims, RegionOccupancy};
+use crate::server::{Load, ServerHeartbeat, ServerId, ServerInfo};
+use crate::spatial::{RegionCoordinate, WorldGrid};
+
+/// Decides when load imbalance across servers warrants a rebalance.
+///
+/// A single skewed observation never triggers a rebalance: the imbalance must
+/// persist for `sustain_window`, and no rebalance fires within `cooldown` of
+/// the previous one. This keeps transient spikes from causing thrashing.
+#[derive(Debug, Clone)]
+pub struct RebalanceTrigger {
+    /// Standard deviation of server load above which the cluster is imbalanced
+    pub imbalance_threshold: f32,
+    /// How long imbalance must persist before rebalancing
+    pub sustain_window: Duration,
+    /// Minimum time between two rebalances
+    pub cooldown: Duration,
+    imbalanced_since: Option<DateTime<Utc>>,
+    last_observed: Option<DateTime<Utc>>,
+    last_rebalance: Option<DateTime<Utc>>,
+}
+
+impl Default for RebalanceTrigger {
+    fn default() -> Self {
+        Self::new(0.15, Duration::seconds(60), Duration::seconds(300))
+    }
+}
+
+impl RebalanceTrigger {
+    /// Creates a new trigger with the given threshold and timings.
+    pub fn new(imbalance_threshold: f32, sustain_window: Duration, cooldown: Duration) -> Self {
+        Self {
+            imbalance_threshold,
+            sustain_window,
+            cooldown,
+            imbalanced_since: None,
+            last_observed: None,
+            last_rebalance: None,
+        }
+    }
+
+    /// Records the latest heartbeats from all servers.
+    pub fn observe(&mut self, heartbeats: &[ServerHeartbeat], now: DateTime<Utc>) {
+        if load_std_dev(heartbeats) > self.imbalance_threshold {
+            self.imbalanced_since.get_or_insert(now);
+        } else {
+            self.imbalanced_since = None;
+        }
+        self.last_observed = Some(now);
+    }
+
+    /// Returns true if imbalance has been sustained and the cooldown has elapsed.
+    pub fn should_rebalance(&self) -> bool {
+        let (Some(since), Some(now)) = (self.imbalanced_since, self.last_observed) else {
+            return false;
+        };
+        if now - since < self.sustain_window {
+            return false;
+        }
+        match self.last_rebalance {
+            Some(last) => now - last >= self.cooldown,
+            None => true,
+        }
+    }
+
+    /// Records that a rebalance was performed, starting the cooldown.
+    pub fn mark_rebalanced(&mut self, now: DateTime<Utc>) {
+        self.last_rebalance = Some(now);
+        self.imbalanced_since = None;
+    }
+}
+
+/// Load of a single region, published by Atlas for autoscaling decisions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegionLoad {
+    /// Region coordinate
+    pub region: RegionCoordinate,
+    /// Server currently owning the region, if any
+    pub server_id: Option<ServerId>,
+    /// Players in the region
+    pub players: u32,
+    /// Player capacity of the owning server
+    pub capacity: u32,
+    /// Region load (0.0 to 1.0)
+    pub load: Load,
+}
+
+/// Gets the most heavily loaded region in a load report.
+pub fn hottest_region(regions: &[RegionLoad]) -> Option<&RegionLoad> {
+    regions.iter().max_by_key(|r| r.load)
+}
+
+/// Largest search radius [`pick_spawn_region`] will use.
+pub const MAX_SPAWN_SEARCH_RADIUS: i64 = 1024;
+
+/// Picks an unoccupied region for a new server when no region was requested.
+///
+/// Returns the free region closest to the centroid of `existing`, searching up
+/// to `desired_radius` (at most [`MAX_SPAWN_SEARCH_RADIUS`]) regions away from
+/// it along each axis. Ties go to the smallest coordinate. With no existing
+/// regions this is the center region. Returns None if every region in the
+/// search cube is occupied.
+pub fn pick_spawn_region(existing: &[RegionCoordinate], desired_radius: i64) -> Option<RegionCoordinate> {
+    if existing.is_empty() {
+        return Some(RegionCoordinate::center());
+    }
+
+    let occupied: HashSet<_> = existing.iter().copied().collect();
+    let n = existing.len() as f64;
+    let centroid = [
+        existing.iter().map(|r| r.x as f64).sum::<f64>() / n,
+        existing.iter().map(|r| r.y as f64).sum::<f64>() / n,
+        existing.iter().map(|r| r.z as f64).sum::<f64>() / n,
+    ];
+    let base = RegionCoordinate::new(
+        centroid[0].round() as i64,
+        centroid[1].round() as i64,
+        centroid[2].round() as i64,
+    );
+    let distance_sq = |r: &RegionCoordinate| {
+        (r.x as f64 - centroid[0]).powi(2) + (r.y as f64 - centroid[1]).powi(2) + (r.z as f64 - centroid[2]).powi(2)
+    };
+
+    let key = |r: &RegionCoordinate| (r.x, r.y, r.z);
+    let mut best: Option<(f64, RegionCoordinate)> = None;
+    for radius in 0..=desired_radius.clamp(0, MAX_SPAWN_SEARCH_RADIUS) {
+        // `base` is within half a region of the centroid on every axis, so
+        // nothing in this shell or beyond can beat the best found so far.
+        let nearest_possible = radius as f64 - 0.5;
+        if best.is_some_and(|(best_d, _)| nearest_possible > 0.0 && nearest_possible.powi(2) > best_d) {
+            break;
+        }
+        for candidate in base.shell(radius) {
+            if occupied.contains(&candidate) {
+                continue;
+            }
+            let d = distance_sq(&candidate);
+            if best.is_none_or(|(best_d, best_r)| d < best_d || (d == best_d && key(&candidate) < key(&best_r))) {
+                best = Some((d, candidate));
+            }
+        }
+    }
+    best.map(|(_, region)| region)
+}
+
+/// Summary of a server instance managed by Maestro.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InstanceSummary {
+    /// Container/instance ID
+    pub instance_id: String,
+    /// Server address once running
+    pub address: Option<String>,
+    /// Region assigned to the instance, if any
+    pub region: Option<RegionCoordinate>,
+}
+
+/// Pool of pre-spawned idle servers ready to take over a region.
+///
+/// Keeping warm instances avoids container start-up time when Atlas needs a
+/// server for a region in a hurry. Maestro tops the pool up to `target_size`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WarmPool {
+    /// Number of idle instances Maestro should keep ready
+    pub target_size: usize,
+    idle: VecDeque<InstanceSummary>,
+}
+
+impl WarmPool {
+    /// Creates an empty pool with the given target size.
+    pub fn new(target_size: usize) -> Self {
+        Self {
+            target_size,
+            idle: VecDeque::new(),
+        }
+    }
+
+    /// Adds a freshly spawned idle instance to the pool.
+    pub fn add_idle(&mut self, instance: InstanceSummary) {
+        self.idle.push_back(instance);
+    }
+
+    /// Takes the oldest warm instance and assigns it to `region`.
+    pub fn acquire(&mut self, region: RegionCoordinate) -> Option<InstanceSummary> {
+        let mut instance = self.idle.pop_front()?;
+        instance.region = Some(region);
+        Some(instance)
+    }
+
+    /// Number of idle instances in the pool.
+    pub fn idle_count(&self) -> usize {
+        self.idle.len()
+    }
+
+    /// Number of instances to spawn to reach the target size.
+    pub fn deficit(&self) -> usize {
+        self.target_size.saturating_sub(self.idle.len())
+    }
+
+    /// Number of idle instances beyond the target size that can be stopped.
+    pub fn surplus(&self) -> usize {
+        self.idle.len().saturating_sub(self.target_size)
+    }
+}
+
+/// Thresholds used by [`CapacityReport::generate`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ScalingPolicy {
+    /// Server load at or above which a server is reported as overloaded
+    pub overload_threshold: f32,
+    /// Cluster utilization above which more servers are recommended
+    pub scale_up_utilization: f32,
+    /// Cluster utilization below which fewer servers are recommended
+    pub scale_down_utilization: f32,
+    /// Regions that should have a server; defaults to the block spanned by the servers
+    #[serde(default)]
+    pub world_dims: Option<RegionDims>,
+}
+
+/// Largest block spanned by the servers that is checked for coverage when a
+/// [`ScalingPolicy`] has no `world_dims`.
+pub const MAX_INFERRED_COVERAGE_REGIONS: usize = 1 << 16;
+
+/// Error returned by [`ScalingPolicy::validate`].
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum InvalidScalingPolicy {
+    /// Scale-up utilization is not a positive, finite number
+    #[error("Scale-up utilization must be positive and finite, got {0}")]
+    ScaleUpUtilization(f32),
+
+    /// Scale-down utilization is NaN or not below the scale-up utilization
+    #[error("Scale-down utilization must be below scale-up utilization, got {0}")]
+    ScaleDownUtilization(f32),
+
+    /// World dims cannot be tracked for coverage
+    #[error(transparent)]
+    WorldDims(#[from] InvalidRegionDims),
+}
+
+impl ScalingPolicy {
+    /// Checks that the thresholds are usable and `world_dims` fits an occupancy map.
+    pub fn validate(&self) -> Result<(), InvalidScalingPolicy> {
+        if !self.scale_up_utilization.is_finite() || self.scale_up_utilization <= 0.0 {
+            return Err(InvalidScalingPolicy::ScaleUpUtilization(self.scale_up_utilization));
+        }
+        if self.scale_down_utilization.is_nan() || self.scale_down_utilization >= self.scale_up_utilization {
+            return Err(InvalidScalingPolicy::ScaleDownUtilization(self.scale_down_utilization));
+        }
+        if let Some(dims) = self.world_dims {
+            RegionOccupancy::new(dims)?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for ScalingPolicy {
+    fn default() -> Self {
+        Self {
+            overload_threshold: 0.9,
+            scale_up_utilization: 0.75,
+            scale_down_utilization: 0.25,
+            world_dims: None,
+        }
+    }
+}
+
+/// Scaling action suggested by a [`CapacityReport`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "action")]
+pub enum ScalingRecommendation {
+    /// Add this many servers
+    ScaleUp { servers: u32 },
+    /// This many servers could be removed
+    ScaleDown { servers: u32 },
+    /// Keep the current cluster size
+    Hold,
+}
+
+/// Capacity-planning summary of the cluster for operators.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CapacityReport {
+    /// Sum of server player capacities
+    pub total_capacity: u64,
+    /// Players currently connected
+    pub used_capacity: u64,
+    /// `used_capacity / total_capacity` (0.0 with no capacity)
+    pub utilization: f32,
+    /// Regions that should have a server but do not
+    pub uncovered_regions: Vec<RegionCoordinate>,
+    /// Servers at or above the policy's overload threshold
+    pub overloaded_servers: Vec<ServerId>,
+    /// Evenness of load across servers (see [`balance_score`])
+    pub balance_score: f32,
+    /// Suggested scaling action
+    pub recommendation: ScalingRecommendation,
+}
+
+impl CapacityReport {
+    /// Builds a report from the registered servers and their latest heartbeats.
+    ///
+    /// Each server covers the region of `grid` containing its center. More
+    /// servers are recommended for every uncovered region plus enough to bring
+    /// utilization under `scale_up_utilization`; fewer are recommended only
+    /// when every region is covered and utilization is below
+    /// `scale_down_utilization`. Without `world_dims`, coverage is checked
+    /// only if the block spanned by the servers has at most
+    /// [`MAX_INFERRED_COVERAGE_REGIONS`] regions.
+    pub fn generate(
+        servers: &[ServerInfo],
+        heartbeats: &[ServerHeartbeat],
+        grid: &WorldGrid,
+        policy: &ScalingPolicy,
+    ) -> Result<CapacityReport, InvalidScalingPolicy> {
+        policy.validate()?;
+
+        let total_capacity: u64 = servers.iter().map(|s| s.capacity as u64).sum();
+        let used_capacity: u64 = heartbeats.iter().map(|h| h.current_connections as u64).sum();
+        let utilization = if total_capacity == 0 {
+            0.0
+        } else {
+            used_capacity as f32 / total_capacity as f32
+        };
+
+        let regions: Vec<RegionCoordinate> = servers.iter().map(|s| grid.region_of(&s.center)).collect();
+        let dims = policy.world_dims.or_else(|| {
+            let first = *regions.first()?;
+            Some(regions.iter().fold(RegionDims::new(first, first), |dims, r| {
+                RegionDims::new(
+                    RegionCoordinate::new(dims.min.x.min(r.x), dims.min.y.min(r.y), dims.min.z.min(r.z)),
+                    RegionCoordinate::new(dims.max.x.max(r.x), dims.max.y.max(r.y), dims.max.z.max(r.z)),
+                )
+            }))
+            .filter(|dims| dims.volume() <= MAX_INFERRED_COVERAGE_REGIONS)
+        });
+        let uncovered_regions = dims
+            .and_then(|dims| RegionOccupancy::new(dims).ok())
+            .map_or_else(Vec::new, |mut occupancy| {
+                for region in &regions {
+                    occupancy.set(region, true);
+                }
+                occupancy.uncovered_regions()
+            });
+
+        let overloaded_servers = heartbeats
+            .iter()
+            .filter(|h| h.should_warn(policy.overload_threshold))
+            .map(|h| h.server_id.clone())
+            .collect();
+
+        let count = servers.len() as u64;
+        let needed = if total_capacity == 0 {
+            count
+        } else {
+            let per_server = total_capacity as f64 / count as f64;
+            (used_capacity as f64 / (per_server * policy.scale_up_utilization as f64)).ceil() as u64
+        };
+        let recommendation = if !uncovered_regions.is_empty() || needed > count {
+            let servers = (uncovered_regions.len() as u64).saturating_add(needed.saturating_sub(count));
+            ScalingRecommendation::ScaleUp {
+                servers: u32::try_from(servers).unwrap_or(u32::MAX),
+            }
+        } else if utilization < policy.scale_down_utilization && count > needed.max(1) {
+            ScalingRecommendation::ScaleDown {
+                servers: u32::try_from(count - needed.max(1)).unwrap_or(u32::MAX),
+            }
+        } else {
+            ScalingRecommendation::Hold
+        };
+
+        Ok(CapacityReport {
+            total_capacity,
+            used_capacity,
+            utilization,
+            uncovered_regions,
+            overloaded_servers,
+            balance_score: balance_score(heartbeats),
+            recommendation,
+        })
+    }
+}
+
+/// Scores how evenly load is spread across servers, from 0.0 to 1.0.
+///
+/// The score is one minus the coefficient of variation of server load, so 1.0
+/// means every server carries the same load. An empty or entirely idle cluster
+/// counts as balanced.
+pub fn balance_score(heartbeats: &[ServerHeartbeat]) -> f32 {
+    if heartbeats.is_empty() {
+        return 1.0;
+    }
+    let mean = heartbeats.iter().map(|h| h.load.get()).sum::<f32>() / heartbeats.len() as f32;
+    if mean <= 0.0 {
+        return 1.0;
+    }
+    (1.0 - load_std_dev(heartbeats) / mean).clamp(0.0, 1.0)
+}
+
+/// Population standard deviation of server load.
+fn load_std_dev(heartbeats: &[ServerHeartbeat]) -> f32 {
+    if heartbeats.is_empty() {
+        return 0.0;
+    }
+    let n = heartbeats.len() as f32;
+    let mean = heartbeats.iter().map(|h| h.load.get()).sum::<f32>() / n;
+    let variance = heartbeats.iter().map(|h| (h.load.get() - mean).powi(2)).sum::<f32>() / n;
+    variance.sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::{ServerId, ServerStatus};
+    use crate::spatial::{RegionBounds, WorldCoordinate};
+
+    fn heartbeats(connections: &[u32]) -> Vec<ServerHeartbeat> {
+        connections
+            .iter()
+            .map(|&c| ServerHeartbeat::new(ServerId::new(), ServerStatus::Running, c, 100))
+            .collect()
+    }
+
+    #[test]
+    fn test_warm_pool_acquire() {
+        let mut pool = WarmPool::new(2);
+        assert_eq!(pool.deficit(), 2);
+        assert_eq!(pool.acquire(RegionCoordinate::center()), None);
+
+        for id in ["warm-1", "warm-2", "warm-3"] {
+            pool.add_idle(InstanceSummary {
+                instance_id: id.to_string(),
+                address: Some("10.0.0.1:8080".to_string()),
+                region: None,
+            });
+        }
+        assert_eq!(pool.surplus(), 1);
+
+        let region = RegionCoordinate::new(3, 0, 1);
+        let instance = pool.acquire(region).unwrap();
+        assert_eq!(instance.instance_id, "warm-1");
+        assert_eq!(instance.region, Some(region));
+        assert_eq!(pool.idle_count(), 2);
+        pool.acquire(region);
+        assert_eq!(pool.deficit(), 1);
+    }
+
+    #[test]
+    fn test_capacity_report() {
+        let grid = WorldGrid::new(1000.0).unwrap();
+        let servers: Vec<ServerInfo> = [0, 2]
+            .into_iter()
+            .map(|x| {
+                let region = RegionCoordinate::new(x, 0, 0);
+                let mut info = ServerInfo::new(
+                    format!("horizon-{}", x),
+                    "127.0.0.1:8080".to_string(),
+                    region,
+                    RegionBounds::from_center(region.to_world_center(grid.region_size()), 500.0),
+                    100,
+                );
+                info.id = ServerId::from(format!("horizon-{}", x));
+                info
+            })
+            .collect();
+        let heartbeats = vec![
+            ServerHeartbeat::new(ServerId::from("horizon-0"), ServerStatus::Running, 95, 100),
+            ServerHeartbeat::new(ServerId::from("horizon-2"), ServerStatus::Running, 20, 100),
+        ];
+
+        let report = CapacityReport::generate(&servers, &heartbeats, &grid, &ScalingPolicy::default()).unwrap();
+        assert_eq!(report.total_capacity, 200);
+        assert_eq!(report.used_capacity, 115);
+        assert_eq!(report.overloaded_servers, vec![ServerId::from("horizon-0")]);
+        assert_eq!(report.uncovered_regions, vec![RegionCoordinate::new(1, 0, 0)]);
+        assert_eq!(report.recommendation, ScalingRecommendation::ScaleUp { servers: 1 });
+        assert!(report.balance_score < 0.5);
+
+        let stalled = ScalingPolicy { scale_up_utilization: 0.0, ..ScalingPolicy::default() };
+        assert_eq!(
+            CapacityReport::generate(&servers, &heartbeats, &grid, &stalled),
+            Err(InvalidScalingPolicy::ScaleUpUtilization(0.0))
+        );
+        let huge = RegionDims::new(RegionCoordinate::new(-1_000_000, 0, 0), RegionCoordinate::new(1_000_000, 1_000, 0));
+        let unbounded = ScalingPolicy { world_dims: Some(huge), ..ScalingPolicy::default() };
+        assert!(matches!(
+            CapacityReport::generate(&servers, &heartbeats, &grid, &unbounded),
+            Err(InvalidScalingPolicy::WorldDims(InvalidRegionDims::TooLarge))
+        ));
+
+        let mut far = servers.clone();
+        far[1].center = WorldCoordinate::new(1e9, 1e9, 1e9);
+        let report = CapacityReport::generate(&far, &heartbeats, &grid, &ScalingPolicy::default()).unwrap();
+        assert!(report.uncovered_regions.is_empty());
+    }
+
+    #[test]
+    fn test_balance_score() {
+        assert_eq!(balance_score(&heartbeats(&[40, 40, 40])), 1.0);
+        assert_eq!(balance_score(&heartbeats(&[0, 0])), 1.0);
+        assert_eq!(balance_score(&[]), 1.0);
+
+        let skewed = balance_score(&heartbeats(&[100, 0, 0, 0]));
+        assert_eq!(skewed, 0.0);
+        let mild = balance_score(&heartbeats(&[60, 40]));
+        assert!(mild > 0.7 && mild < 0.9, "score was {}", mild);
+    }
+
+    #[test]
+    fn test_hottest_region() {
+        let regions: Vec<RegionLoad> = [(0, 20), (1, 90), (2, 45)]
+            .into_iter()
+            .map(|(x, players)| RegionLoad {
+                region: RegionCoordinate::new(x, 0, 0),
+                server_id: Some(ServerId::from(format!("horizon-{}", x))),
+                players,
+                capacity: 100,
+                load: Load::from_ratio(players, 100),
+            })
+            .collect();
+        assert_eq!(hottest_region(&regions).unwrap().region, RegionCoordinate::new(1, 0, 0));
+        assert_eq!(hottest_region(&[]), None);
+    }
+
+    #[test]
+    fn test_pick_spawn_region() {
+        // A ring around the origin leaves the inner cell free.
+        let mut ring = Vec::new();
+        for x in -1..=1 {
+            for y in -1..=1 {
+                if (x, y) != (0, 0) {
+                    ring.push(RegionCoordinate::new(x, y, 0));
+                }
+            }
+        }
+        assert_eq!(pick_spawn_region(&ring, 2), Some(RegionCoordinate::center()));
+
+        // A full 3x3x3 block pushes the pick to a face-adjacent outer cell.
+        let mut block = ring.clone();
+        block.push(RegionCoordinate::center());
+        for x in -1..=1 {
+            for y in -1..=1 {
+                block.push(RegionCoordinate::new(x, y, 1));
+                block.push(RegionCoordinate::new(x, y, -1));
+            }
+        }
+        let picked = pick_spawn_region(&block, 2).unwrap();
+        assert!(!block.contains(&picked));
+        assert_eq!(picked.manhattan_distance(&RegionCoordinate::center()), 2);
+        assert_eq!(pick_spawn_region(&block, 1), None);
+        assert_eq!(pick_spawn_region(&[], 1), Some(RegionCoordinate::center()));
+
+        // Huge radii are clamped and stop early once a free region is found.
+        assert_eq!(pick_spawn_region(&ring, i64::MAX), Some(RegionCoordinate::center()));
+        let edge = [RegionCoordinate::new(i64::MAX, 0, 0)];
+        let picked = pick_spawn_region(&edge, i64::MAX).unwrap();
+        assert_eq!(picked.chebyshev_distance(&edge[0]), 1);
+    }
+
+    #[test]
+    fn test_transient_spike_does_not_trigger() {
+        let mut trigger = RebalanceTrigger::default();
+        let start = Utc::now();
+        trigger.observe(&heartbeats(&[95, 5]), start);
+        trigger.observe(&heartbeats(&[50, 50]), start + Duration::seconds(10));
+        trigger.observe(&heartbeats(&[50, 50]), start + Duration::seconds(120));
+        assert!(!trigger.should_rebalance());
+    }
+
+    #[test]
+    fn test_sustained_imbalance_triggers_with_cooldown() {
+        let mut trigger = RebalanceTrigger::default();
+        let start = Utc::now();
+        trigger.observe(&heartbeats(&[95, 5]), start);
+        assert!(!trigger.should_rebalance());
+        trigger.observe(&heartbeats(&[90, 10]), start + Duration::seconds(61));
+        assert!(trigger.should_rebalance());
+
+        trigger.mark_rebalanced(start + Duration::seconds(61));
+        trigger.observe(&heartbeats(&[95, 5]), start + Duration::seconds(70));
+        trigger.observe(&heartbeats(&[95, 5]), start + Duration::seconds(200));
+        assert!(!trigger.should_rebalance());
+        trigger.observe(&heartbeats(&[95, 5]), start + Duration::seconds(400));
+        assert!(trigger.should_rebalance());
+    }
+}
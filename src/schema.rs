@@ -0,0 +1,315 @@
+//! Schema fingerprints for detecting message layout mismatches.
+//!
+//! Each message kind is fingerprinted from the JSON shape of a fully populated
+//! sample value, so adding, removing, renaming or retyping a field anywhere in
+//! a message (including nested structs) changes the fingerprint without any
+//! hand-maintained table. Services exchange fingerprints during the handshake.
+
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::codec;
+use crate::health::{ComponentHealth, HealthCheck, HealthCheckRequest};
+use crate::interest::{GhostEntity, InterestConfig};
+use crate::messages::{AtlasMessage, AtlasToMaestroMessage, HorizonMessage, MaestroMessage, Message};
+use crate::player::{DisconnectReason, PlayerId, PlayerInfo, PlayerState};
+use crate::scaling::RegionLoad;
+use crate::server::{
+    Load, RegistrationFailureReason, RegistrationResponse, ServerHeartbeat, ServerId, ServerInfo, ServerRegistration,
+    ServerStatus, ShutdownReason, SpawnServerRequest, SpawnServerResponse,
+};
+use crate::spatial::{RegionBounds, RegionCoordinate, WorldCoordinate};
+use crate::transfer::{TransferError, TransferReason, TransferRequest, TransferToken};
+use crate::units::{Millis, Seconds};
+
+/// Gets the schema fingerprint of a message kind (0 for unknown kinds).
+///
+/// A mismatch for the same kind means the two sides disagree on that
+/// message's field layout.
+pub fn message_fingerprint(kind: &str) -> u64 {
+    fingerprints().get(kind).copied().unwrap_or(0)
+}
+
+/// Gets the fingerprints of all message kinds, for sending in a handshake.
+pub fn message_fingerprints() -> impl Iterator<Item = (&'static str, u64)> {
+    fingerprints().iter().map(|(kind, fingerprint)| (*kind, *fingerprint))
+}
+
+fn fingerprints() -> &'static HashMap<&'static str, u64> {
+    static FINGERPRINTS: OnceLock<HashMap<&'static str, u64>> = OnceLock::new();
+    FINGERPRINTS.get_or_init(|| {
+        message_samples()
+            .into_iter()
+            .map(|(kind, value)| (kind, layout_fingerprint(kind, &value)))
+            .collect()
+    })
+}
+
+fn layout_fingerprint(kind: &str, message: &Value) -> u64 {
+    let mut layout = String::from(kind);
+    write_layout(&message["payload"], &mut layout);
+    codec::checksum(layout.as_bytes())
+}
+
+/// Appends the shape of a JSON value: object keys (sorted) with the shape of
+/// their values, the shape of an array's first element, and scalar types.
+fn write_layout(value: &Value, out: &mut String) {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(_) => out.push_str("bool"),
+        Value::Number(n) if n.is_f64() => out.push_str("float"),
+        Value::Number(_) => out.push_str("int"),
+        Value::String(_) => out.push_str("string"),
+        Value::Array(items) => {
+            out.push('[');
+            if let Some(first) = items.first() {
+                write_layout(first, out);
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort_unstable();
+            out.push('{');
+            for (i, key) in keys.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(key);
+                out.push(':');
+                write_layout(&map[key], out);
+            }
+            out.push('}');
+        }
+    }
+}
+
+fn sample<M: Message + Serialize>(message: M) -> (&'static str, Value) {
+    let value = serde_json::to_value(&message).expect("message samples always serialize");
+    (message.kind(), value)
+}
+
+/// One fully populated value of every message variant.
+///
+/// Optional fields are set so their contents are part of the layout; maps are
+/// left empty since their keys are data, not schema. The tests check that
+/// every variant of every message enum has a sample here.
+fn message_samples() -> Vec<(&'static str, Value)> {
+    let player_id = PlayerId::from("player");
+    let server_id = ServerId::from("server");
+    let region = RegionCoordinate::new(1, 2, 3);
+    let position = WorldCoordinate::new(1.5, 2.5, 3.5);
+    let bounds = RegionBounds::from_center(position, 500.0);
+
+    let mut server = ServerInfo::new("name".to_string(), "127.0.0.1:8080".to_string(), region, bounds, 100);
+    server.region_size = Some(1000.0);
+
+    let mut registration = ServerRegistration::new(server.clone());
+    registration.idle_timeout_secs = Some(Seconds(60));
+
+    let heartbeat = ServerHeartbeat::new(server_id.clone(), ServerStatus::Running, 10, 100);
+
+    let mut component = ComponentHealth::healthy("network");
+    component.details = Some("details".to_string());
+    component.response_time_ms = Some(Millis(5));
+    let mut health = HealthCheck::healthy(server_id.clone(), 10, 100);
+    health.components = vec![component];
+    health.message = Some("message".to_string());
+
+    let token = TransferToken::new(
+        player_id.clone(),
+        server_id.clone(),
+        server_id.clone(),
+        "127.0.0.1:8081".to_string(),
+        b"schema",
+    );
+
+    let mut player = PlayerInfo::new(player_id.clone(), "name".to_string());
+    player.current_server = Some(server_id.clone());
+
+    vec![
+        sample(HorizonMessage::Register(registration)),
+        sample(HorizonMessage::Heartbeat(heartbeat)),
+        sample(HorizonMessage::HealthResponse(health)),
+        sample(HorizonMessage::PlayerConnected { player_id: player_id.clone(), position }),
+        sample(HorizonMessage::PlayerDisconnected {
+            player_id: player_id.clone(),
+            reason: DisconnectReason::Kicked { reason: "reason".to_string() },
+        }),
+        sample(HorizonMessage::PlayerPositionUpdate { player_id: player_id.clone(), position, velocity: position }),
+        sample(HorizonMessage::TransferRequest(TransferRequest {
+            player_id: player_id.clone(),
+            source_server: server_id.clone(),
+            target_server: server_id.clone(),
+            target_position: position,
+            reason: TransferReason::RegionBoundary,
+            priority: 1,
+        })),
+        sample(HorizonMessage::TransferComplete {
+            player_id: player_id.clone(),
+            success: true,
+            error: Some("error".to_string()),
+        }),
+        sample(HorizonMessage::TransferAccepted { player_id: player_id.clone(), token_id: "token".to_string() }),
+        sample(HorizonMessage::TransferDeclined {
+            token_id: "token".to_string(),
+            player_id: player_id.clone(),
+            reason: TransferError::TransferRejected("reason".to_string()),
+        }),
+        sample(HorizonMessage::TransferRollback {
+            token_id: "token".to_string(),
+            player_id: player_id.clone(),
+            reason: "reason".to_string(),
+        }),
+        sample(HorizonMessage::Shutdown {
+            server_id: server_id.clone(),
+            player_count: 1,
+            reason: ShutdownReason::Planned,
+        }),
+        sample(HorizonMessage::PlayerListRequest { cursor: Some("cursor".to_string()), page_size: 1 }),
+        sample(HorizonMessage::RegionOwnershipReleased { region, player_count: 1 }),
+        sample(HorizonMessage::CapacityWarning { server_id: server_id.clone(), current: 1, capacity: 1, threshold: 0.5 }),
+        sample(AtlasMessage::RegistrationResponse(RegistrationResponse {
+            success: true,
+            server_id: server_id.clone(),
+            message: "message".to_string(),
+            heartbeat_interval_secs: Seconds(5),
+            adjacent_servers: vec![server.clone()],
+            failure_reason: Some(RegistrationFailureReason::RegionOccupied { region, occupant: server_id.clone() }),
+            idle_timeout_secs: Some(Seconds(60)),
+        })),
+        sample(AtlasMessage::HealthCheckRequest(HealthCheckRequest { include_components: true, include_metrics: true })),
+        sample(AtlasMessage::InitiateTransfer {
+            player_id: player_id.clone(),
+            target_server: server.clone(),
+            token: token.clone(),
+        }),
+        sample(AtlasMessage::AcceptTransfer { token, player_state: PlayerState::new(player.clone()) }),
+        sample(AtlasMessage::CancelTransfer { player_id: player_id.clone(), reason: "reason".to_string() }),
+        sample(AtlasMessage::PrepareShutdown { deadline_secs: 1 }),
+        sample(AtlasMessage::AdjacentServersUpdate { servers: vec![server] }),
+        sample(AtlasMessage::ConfigUpdate { config: serde_json::json!({}) }),
+        sample(AtlasMessage::InterestConfig(InterestConfig {
+            default_view_distance: 1.5,
+            max_view_distance: 2.5,
+            cell_size: 1.5,
+        })),
+        sample(AtlasMessage::PlayerListPage {
+            page: 1,
+            page_size: 1,
+            players: vec![player],
+            has_more: true,
+            next_cursor: Some("cursor".to_string()),
+        }),
+        sample(AtlasMessage::TransferRegionOwnership { region, to: server_id.clone() }),
+        sample(AtlasMessage::GhostUpdate {
+            ghosts: vec![GhostEntity {
+                player_id,
+                position,
+                velocity: position,
+                authoritative_server: server_id.clone(),
+            }],
+        }),
+        sample(AtlasToMaestroMessage::SpawnServer(SpawnServerRequest {
+            region_coord: region,
+            bounds,
+            name: Some("name".to_string()),
+            environment: HashMap::new(),
+            affinity: Some("affinity".to_string()),
+            anti_affinity: vec!["label".to_string()],
+        })),
+        sample(AtlasToMaestroMessage::StopServer { instance_id: "instance".to_string(), graceful: true }),
+        sample(AtlasToMaestroMessage::GetServerStats { instance_id: "instance".to_string() }),
+        sample(AtlasToMaestroMessage::ScaleCluster { target_count: 1 }),
+        sample(AtlasToMaestroMessage::RegionLoadReport {
+            regions: vec![RegionLoad {
+                region,
+                server_id: Some(server_id),
+                players: 1,
+                capacity: 1,
+                load: Load::FULL,
+            }],
+        }),
+        sample(MaestroMessage::SpawnResponse(SpawnServerResponse {
+            success: true,
+            instance_id: "instance".to_string(),
+            address: Some("127.0.0.1:8080".to_string()),
+            error: Some("error".to_string()),
+        })),
+        sample(MaestroMessage::ServerStopped {
+            instance_id: "instance".to_string(),
+            exit_code: Some(1),
+            reason: ShutdownReason::Planned,
+        }),
+        sample(MaestroMessage::ServerStats {
+            instance_id: "instance".to_string(),
+            cpu_percent: 1.5,
+            memory_mb: 1,
+            running: true,
+        }),
+        sample(MaestroMessage::ClusterScaled { current_count: 1, target_count: 1 }),
+        sample(MaestroMessage::RegionSplit { parent: region, children: vec![region] }),
+        sample(MaestroMessage::RegionMerged { children: vec![region], parent: region }),
+        sample(MaestroMessage::Error { operation: "operation".to_string(), message: "message".to_string() }),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::de::DeserializeOwned;
+    use std::collections::HashSet;
+
+    /// Variant names of a message enum, as listed by serde for an unknown tag.
+    fn variants_of<T: DeserializeOwned>() -> Vec<String> {
+        let err = serde_json::from_str::<T>(r#"{"type":"__probe__","payload":null}"#)
+            .err()
+            .expect("probe tag is not a variant")
+            .to_string();
+        let list = err.split("expected one of ").nth(1).expect("serde lists the variants");
+        list.split(", ")
+            .map(|v| v.split('`').nth(1).expect("variant names are quoted").to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_every_variant_has_a_sample() {
+        let sampled: HashSet<&str> = message_samples().iter().map(|(kind, _)| *kind).collect();
+        let mut variants = variants_of::<HorizonMessage>();
+        variants.extend(variants_of::<AtlasMessage>());
+        variants.extend(variants_of::<AtlasToMaestroMessage>());
+        variants.extend(variants_of::<MaestroMessage>());
+
+        let variants: HashSet<&str> = variants.iter().map(String::as_str).collect();
+        assert_eq!(sampled, variants);
+        assert_eq!(message_samples().len(), variants.len());
+        for (kind, value) in message_samples() {
+            assert_eq!(value["type"], kind);
+        }
+    }
+
+    #[test]
+    fn test_message_fingerprints() {
+        // Golden values: these change whenever a message's layout changes.
+        assert_eq!(message_fingerprint("TransferAccepted"), 0x5190_c949_242a_a9b7);
+        assert_eq!(message_fingerprint("Register"), 0xf95e_de1c_6840_35bc);
+        assert_eq!(message_fingerprint("NoSuchMessage"), 0);
+        assert_eq!(message_fingerprints().count(), message_samples().len());
+    }
+
+    #[test]
+    fn test_nested_field_changes_fingerprint() {
+        let (kind, mut value) = message_samples().into_iter().find(|(k, _)| *k == "Register").unwrap();
+        let original = layout_fingerprint(kind, &value);
+
+        value["payload"]["server"]["extra"] = serde_json::json!(1);
+        assert_ne!(layout_fingerprint(kind, &value), original);
+
+        value["payload"]["server"].as_object_mut().unwrap().remove("extra");
+        assert_eq!(layout_fingerprint(kind, &value), original);
+        value["payload"]["server"]["capacity"] = serde_json::json!("100");
+        assert_ne!(layout_fingerprint(kind, &value), original);
+    }
+}
@@ -7,7 +7,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use chrono::{DateTime, Utc};
 
-use crate::spatial::{RegionBounds, RegionCoordinate, WorldCoordinate};
+use crate::spatial::{RegionBounds, RegionCoordinate, WorldCoordinate, WorldGrid};
+use crate::units::Seconds;
 
 /// Unique identifier for a Horizon server instance.
 /// Uses String for JSON API compatibility.
@@ -49,11 +50,83 @@ impl From<&str> for ServerId {
     }
 }
 
+/// Server load as a fraction of capacity, always within `[0.0, 1.0]`.
+///
+/// Out-of-range values are clamped on construction and NaN is rejected, so
+/// loads can be totally ordered. Serializes as a plain number.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(try_from = "f32", into = "f32")]
+pub struct Load(f32);
+
+/// Error returned when constructing a [`Load`] from NaN.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("Load must be a number, got NaN")]
+pub struct InvalidLoad;
+
+impl Load {
+    /// No load.
+    pub const ZERO: Load = Load(0.0);
+    /// Fully loaded.
+    pub const FULL: Load = Load(1.0);
+
+    /// Creates a load, clamping to `[0.0, 1.0]` and rejecting NaN.
+    pub fn new(value: f32) -> Result<Self, InvalidLoad> {
+        if value.is_nan() {
+            return Err(InvalidLoad);
+        }
+        // Adding 0.0 folds -0.0 into 0.0 so equality and ordering agree.
+        Ok(Self(value.clamp(0.0, 1.0) + 0.0))
+    }
+
+    /// Creates a load from a connection count and capacity (zero if capacity is zero).
+    pub fn from_ratio(current: u32, capacity: u32) -> Self {
+        if capacity == 0 {
+            Self::ZERO
+        } else {
+            Self((current as f32 / capacity as f32).min(1.0))
+        }
+    }
+
+    /// Gets the load as a plain float.
+    pub fn get(self) -> f32 {
+        self.0
+    }
+}
+
+impl Eq for Load {}
+
+impl PartialOrd for Load {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Load {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl TryFrom<f32> for Load {
+    type Error = InvalidLoad;
+
+    fn try_from(value: f32) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl From<Load> for f32 {
+    fn from(load: Load) -> Self {
+        load.0
+    }
+}
+
 /// Current status of a Horizon server instance.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum ServerStatus {
     /// Server is starting up
+    #[default]
     Starting,
     /// Server is running and accepting connections
     Running,
@@ -65,12 +138,44 @@ pub enum ServerStatus {
     Error,
 }
 
-impl Default for ServerStatus {
-    fn default() -> Self {
-        Self::Starting
+/// Why a Horizon server stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ShutdownReason {
+    /// Planned shutdown or drain
+    Planned,
+    /// Process crashed
+    Crashed,
+    /// Process was killed for running out of memory
+    OutOfMemory,
+    /// Region was migrated to another server
+    Migrated,
+    /// Reason was not reported
+    #[default]
+    Unknown,
+}
+
+impl ShutdownReason {
+    /// Returns true for expected shutdowns that should not raise alerts.
+    pub fn is_expected(&self) -> bool {
+        matches!(self, Self::Planned | Self::Migrated)
     }
 }
 
+/// Network transport a server accepts client connections on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Transport {
+    Tcp,
+    Udp,
+    Quic,
+    WebSocket,
+}
+
+fn default_transports() -> Vec<Transport> {
+    vec![Transport::Tcp]
+}
+
 /// Basic server information for registration and discovery.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerInfo {
@@ -90,6 +195,18 @@ pub struct ServerInfo {
     pub capacity: u32,
     /// Server version string
     pub version: String,
+    /// Transports clients can connect with
+    #[serde(default = "default_transports")]
+    pub transports: Vec<Transport>,
+    /// Edge length of one region in world units, if known
+    #[serde(default)]
+    pub region_size: Option<f64>,
+    /// Generation of the world grid `region_size` belongs to
+    #[serde(default)]
+    pub grid_generation: u64,
+    /// Operator-defined labels (e.g. `env=prod`) used for selection
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
 }
 
 impl ServerInfo {
@@ -110,8 +227,38 @@ impl ServerInfo {
             center: bounds.center(),
             capacity,
             version: env!("CARGO_PKG_VERSION").to_string(),
+            transports: default_transports(),
+            region_size: None,
+            grid_generation: 0,
+            labels: HashMap::new(),
         }
     }
+
+    /// Returns true if the server accepts connections over `transport`.
+    pub fn supports(&self, transport: Transport) -> bool {
+        self.transports.contains(&transport)
+    }
+
+    /// Returns true if the server has every label in `selector` with the same value.
+    ///
+    /// An empty selector matches every server.
+    pub fn matches_selector(&self, selector: &HashMap<String, String>) -> bool {
+        selector.iter().all(|(key, value)| self.labels.get(key) == Some(value))
+    }
+
+    /// Gets the world grid this server's region belongs to.
+    ///
+    /// Returns None if the server info does not carry a valid region size.
+    pub fn grid(&self) -> Option<WorldGrid> {
+        self.region_size
+            .and_then(|region_size| WorldGrid::with_generation(region_size, self.grid_generation).ok())
+    }
+
+    /// Records the world grid this server's region belongs to.
+    pub fn set_grid(&mut self, grid: &WorldGrid) {
+        self.region_size = Some(grid.region_size());
+        self.grid_generation = grid.grid_generation();
+    }
 }
 
 /// Server registration request sent from Horizon to Atlas.
@@ -126,6 +273,9 @@ pub struct ServerRegistration {
     /// Optional metadata for custom properties
     #[serde(default)]
     pub metadata: std::collections::HashMap<String, serde_json::Value>,
+    /// Connection idle timeout the server proposes (see `negotiate_timeout`)
+    #[serde(default)]
+    pub idle_timeout_secs: Option<Seconds>,
 }
 
 impl ServerRegistration {
@@ -136,6 +286,7 @@ impl ServerRegistration {
             status: ServerStatus::Starting,
             registered_at: Utc::now(),
             metadata: std::collections::HashMap::new(),
+            idle_timeout_secs: None,
         }
     }
 }
@@ -150,7 +301,7 @@ pub struct ServerHeartbeat {
     /// Current number of connected players
     pub current_connections: u32,
     /// Server load (0.0 to 1.0)
-    pub load: f32,
+    pub load: Load,
     /// Timestamp of this heartbeat
     pub timestamp: DateTime<Utc>,
     /// Average tick time in milliseconds
@@ -159,6 +310,12 @@ pub struct ServerHeartbeat {
     /// Memory usage in bytes
     #[serde(default)]
     pub memory_bytes: u64,
+    /// Target tick duration in milliseconds (0 if not reported)
+    #[serde(default)]
+    pub tick_budget_ms: f64,
+    /// Ticks that exceeded the budget since the previous heartbeat
+    #[serde(default)]
+    pub tick_overruns: u32,
 }
 
 impl ServerHeartbeat {
@@ -169,24 +326,92 @@ impl ServerHeartbeat {
         current_connections: u32,
         capacity: u32,
     ) -> Self {
-        let load = if capacity > 0 {
-            current_connections as f32 / capacity as f32
-        } else {
-            0.0
-        };
-
         Self {
             server_id,
             status,
             current_connections,
-            load,
+            load: Load::from_ratio(current_connections, capacity),
             timestamp: Utc::now(),
             avg_tick_ms: 0.0,
             memory_bytes: 0,
+            tick_budget_ms: 0.0,
+            tick_overruns: 0,
+        }
+    }
+
+    /// Returns true if load has reached `threshold` (0.0 to 1.0).
+    ///
+    /// Servers use this to send `HorizonMessage::CapacityWarning` as soon as
+    /// they near capacity instead of waiting for Atlas's next scaling pass.
+    pub fn should_warn(&self, threshold: f32) -> bool {
+        self.load.get() >= threshold
+    }
+
+    /// Ratio of average tick time to the tick budget.
+    ///
+    /// Values above 1.0 mean ticks are running over budget on average. Returns
+    /// 0.0 when the server does not report a budget.
+    pub fn overrun_ratio(&self) -> f32 {
+        if self.tick_budget_ms > 0.0 {
+            (self.avg_tick_ms / self.tick_budget_ms) as f32
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Uptime below which an idle server is assumed to still be starting.
+const STARTUP_GRACE: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Tick budget assumed when a heartbeat does not report one (20 ticks per second).
+const FALLBACK_TICK_BUDGET_MS: f64 = 50.0;
+
+/// Server status inferred from heartbeat metrics by [`infer_status`].
+///
+/// Local to the observer and never sent on the wire; use
+/// [`InferredStatus::status`] where a [`ServerStatus`] is needed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InferredStatus {
+    /// Server looks like it is still starting up
+    Starting,
+    /// Server is running within its tick budget
+    Running,
+    /// Server is running but its ticks are falling behind
+    Degraded,
+}
+
+impl InferredStatus {
+    /// Closest wire status; a degraded server is still `Running`.
+    pub fn status(self) -> ServerStatus {
+        match self {
+            Self::Starting => ServerStatus::Starting,
+            Self::Running | Self::Degraded => ServerStatus::Running,
         }
     }
 }
 
+/// Infers a server's status from heartbeat metrics, ignoring `heartbeat.status`.
+///
+/// Best-effort fallback for servers that report metrics but not a reliable
+/// status: an idle server within its first minute is `Starting`, one whose
+/// average tick exceeds its budget is `Degraded`, and anything else `Running`.
+/// Without a reported budget, ticks slower than 50 ms count as over budget.
+pub fn infer_status(heartbeat: &ServerHeartbeat, uptime: std::time::Duration) -> InferredStatus {
+    if uptime < STARTUP_GRACE && heartbeat.current_connections == 0 && heartbeat.load == Load::ZERO {
+        return InferredStatus::Starting;
+    }
+    let over_budget = if heartbeat.tick_budget_ms > 0.0 {
+        heartbeat.overrun_ratio() > 1.0
+    } else {
+        heartbeat.avg_tick_ms > FALLBACK_TICK_BUDGET_MS
+    };
+    if over_budget {
+        InferredStatus::Degraded
+    } else {
+        InferredStatus::Running
+    }
+}
+
 /// Response from Atlas when a server registers.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RegistrationResponse {
@@ -196,14 +421,69 @@ pub struct RegistrationResponse {
     pub server_id: ServerId,
     /// Message describing the result
     pub message: String,
-    /// Heartbeat interval in seconds
-    pub heartbeat_interval_secs: u32,
+    /// Heartbeat interval
+    pub heartbeat_interval_secs: Seconds,
     /// List of adjacent servers for cross-region communication
     #[serde(default)]
     pub adjacent_servers: Vec<ServerInfo>,
+    /// Structured reason when registration was rejected
+    #[serde(default)]
+    pub failure_reason: Option<RegistrationFailureReason>,
+    /// Connection idle timeout both sides agreed on
+    #[serde(default)]
+    pub idle_timeout_secs: Option<Seconds>,
+}
+
+impl RegistrationResponse {
+    /// Creates a rejection for a region that already has an owning server.
+    ///
+    /// The registering server can pick a different region or join as a replica.
+    pub fn region_occupied(server_id: ServerId, region: RegionCoordinate, occupant: ServerId) -> Self {
+        Self {
+            success: false,
+            message: format!(
+                "Region ({}, {}, {}) is already owned by server {}",
+                region.x, region.y, region.z, occupant
+            ),
+            server_id,
+            heartbeat_interval_secs: Seconds(0),
+            adjacent_servers: Vec::new(),
+            failure_reason: Some(RegistrationFailureReason::RegionOccupied { region, occupant }),
+            idle_timeout_secs: None,
+        }
+    }
+}
+
+/// Agrees on a connection idle timeout during registration.
+///
+/// Atlas takes the smaller of the server's proposal and its own maximum, and
+/// uses its maximum when the server made no proposal. The result goes in
+/// `RegistrationResponse::idle_timeout_secs`.
+pub fn negotiate_timeout(proposed: Option<Seconds>, atlas_max: Seconds) -> Seconds {
+    proposed.map_or(atlas_max, |proposed| proposed.min(atlas_max))
+}
+
+/// Reason Atlas rejected a server registration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "reason")]
+pub enum RegistrationFailureReason {
+    /// The requested region already has an owning server at capacity
+    RegionOccupied {
+        region: RegionCoordinate,
+        occupant: ServerId,
+    },
+    /// Registration was rejected for another reason
+    Rejected { message: String },
 }
 
 /// Request from Atlas to Maestro to spawn a new Horizon instance.
+///
+/// `affinity` and `anti_affinity` are placement labels. Maestro tags each
+/// instance it spawns with its `affinity` label, prefers a host already running
+/// instances with the same label, and never places the instance on a host
+/// running an instance labelled with any entry of `anti_affinity`. Giving
+/// adjacent regions each other's labels as anti-affinity keeps a single host
+/// failure from taking out a whole neighbourhood.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SpawnServerRequest {
     /// Requested region coordinate
@@ -215,6 +495,12 @@ pub struct SpawnServerRequest {
     /// Environment variables to pass to the container
     #[serde(default)]
     pub environment: std::collections::HashMap<String, String>,
+    /// Placement label to co-locate with
+    #[serde(default)]
+    pub affinity: Option<String>,
+    /// Placement labels that must not share a host with this instance
+    #[serde(default)]
+    pub anti_affinity: Vec<String>,
 }
 
 /// Response from Maestro after spawning a server.
@@ -300,12 +586,16 @@ pub struct AdjacentServerInfo {
 pub struct ApiServerHeartbeat {
     pub server_id: String,
     pub current_connections: u32,
-    pub load: f32,
+    pub load: Load,
     pub accepting_connections: bool,
     #[serde(default)]
     pub avg_tick_ms: f64,
     #[serde(default)]
     pub memory_bytes: u64,
+    #[serde(default)]
+    pub tick_budget_ms: f64,
+    #[serde(default)]
+    pub tick_overruns: u32,
 }
 
 /// API heartbeat response.
@@ -315,6 +605,22 @@ pub struct ApiHeartbeatResponse {
     pub message: String,
     #[serde(default)]
     pub commands: Vec<ServerCommand>,
+    /// Heartbeat interval Atlas wants from now on (e.g. shorter while degraded)
+    #[serde(default)]
+    pub next_interval_secs: Option<u32>,
+}
+
+impl ApiHeartbeatResponse {
+    /// Gets the interval to wait before the next heartbeat.
+    ///
+    /// Returns the hint from Atlas if present, otherwise `current`. A zero
+    /// hint is ignored so a bad response cannot make the server spin.
+    pub fn next_interval(&self, current: Seconds) -> Seconds {
+        match self.next_interval_secs {
+            Some(secs) if secs > 0 => Seconds(secs),
+            _ => current,
+        }
+    }
 }
 
 /// Commands from Atlas to Horizon.
@@ -330,6 +636,41 @@ pub enum ServerCommand {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_heartbeat_response_interval_hint() {
+        let json = r#"{"success": true, "message": "ok"}"#;
+        let response: ApiHeartbeatResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.next_interval_secs, None);
+        assert_eq!(response.next_interval(Seconds(10)), Seconds(10));
+
+        let response = ApiHeartbeatResponse { next_interval_secs: Some(2), ..response };
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["next_interval_secs"], 2);
+        let response: ApiHeartbeatResponse = serde_json::from_value(json).unwrap();
+        assert_eq!(response.next_interval(Seconds(10)), Seconds(2));
+
+        let zero = ApiHeartbeatResponse { next_interval_secs: Some(0), ..response };
+        assert_eq!(zero.next_interval(Seconds(10)), Seconds(10));
+    }
+
+    #[test]
+    fn test_negotiate_idle_timeout() {
+        let info = ServerInfo::new(
+            "test-server".to_string(),
+            "127.0.0.1:8080".to_string(),
+            RegionCoordinate::center(),
+            RegionBounds::default(),
+            100,
+        );
+        let mut reg = ServerRegistration::new(info);
+        reg.idle_timeout_secs = Some(Seconds(300));
+        let reg: ServerRegistration = serde_json::from_str(&serde_json::to_string(&reg).unwrap()).unwrap();
+
+        assert_eq!(negotiate_timeout(reg.idle_timeout_secs, Seconds(120)), Seconds(120));
+        assert_eq!(negotiate_timeout(Some(Seconds(30)), Seconds(120)), Seconds(30));
+        assert_eq!(negotiate_timeout(None, Seconds(120)), Seconds(120));
+    }
+
     #[test]
     fn test_server_registration() {
         let info = ServerInfo::new(
@@ -343,6 +684,116 @@ mod tests {
         assert_eq!(reg.status, ServerStatus::Starting);
     }
 
+    #[test]
+    fn test_server_transports() {
+        let mut info = ServerInfo::new(
+            "test-server".to_string(),
+            "127.0.0.1:8080".to_string(),
+            RegionCoordinate::center(),
+            RegionBounds::default(),
+            100,
+        );
+        assert!(info.supports(Transport::Tcp));
+        assert!(!info.supports(Transport::Quic));
+
+        info.transports = vec![Transport::Quic, Transport::WebSocket];
+        let json = serde_json::to_value(&info).unwrap();
+        assert_eq!(json["transports"], serde_json::json!(["quic", "web_socket"]));
+        let restored: ServerInfo = serde_json::from_value(json.clone()).unwrap();
+        assert!(restored.supports(Transport::WebSocket));
+
+        let mut legacy = json;
+        legacy.as_object_mut().unwrap().remove("transports");
+        let restored: ServerInfo = serde_json::from_value(legacy).unwrap();
+        assert_eq!(restored.transports, vec![Transport::Tcp]);
+    }
+
+    #[test]
+    fn test_server_info_region_size() {
+        let region = RegionCoordinate::new(2, 0, -1);
+        let center = region.to_world_center(1000.0);
+        let mut info = ServerInfo::new(
+            "test-server".to_string(),
+            "127.0.0.1:8080".to_string(),
+            region,
+            RegionBounds::from_center(center, 500.0),
+            100,
+        );
+        assert!(info.grid().is_none());
+
+        let mut resized = WorldGrid::new(500.0).unwrap();
+        resized.resize(1000.0).unwrap();
+        info.set_grid(&resized);
+        let json = serde_json::to_value(&info).unwrap();
+        assert_eq!(json["region_size"], 1000.0);
+        assert_eq!(json["grid_generation"], 1);
+        let restored: ServerInfo = serde_json::from_value(json.clone()).unwrap();
+        let grid = restored.grid().unwrap();
+        assert_eq!(grid, resized);
+        let mut invalid = restored.clone();
+        invalid.region_size = Some(0.0);
+        assert!(invalid.grid().is_none());
+        assert_eq!(grid.region_of(&WorldCoordinate::new(2100.0, 10.0, -900.0)), region);
+
+        let mut legacy = json;
+        legacy.as_object_mut().unwrap().remove("region_size");
+        legacy.as_object_mut().unwrap().remove("grid_generation");
+        let restored: ServerInfo = serde_json::from_value(legacy).unwrap();
+        assert_eq!(restored.region_size, None);
+        assert_eq!(restored.grid_generation, 0);
+    }
+
+    #[test]
+    fn test_spawn_request_placement_hints() {
+        let json = serde_json::json!({
+            "region_coord": {"x": 1, "y": 0, "z": 0},
+            "bounds": RegionBounds::default(),
+            "name": null,
+        });
+        let request: SpawnServerRequest = serde_json::from_value(json).unwrap();
+        assert_eq!(request.affinity, None);
+        assert!(request.anti_affinity.is_empty());
+
+        let request = SpawnServerRequest {
+            affinity: Some("region-1-0-0".to_string()),
+            anti_affinity: vec!["region-0-0-0".to_string(), "region-2-0-0".to_string()],
+            ..request
+        };
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["affinity"], "region-1-0-0");
+        assert_eq!(json["anti_affinity"], serde_json::json!(["region-0-0-0", "region-2-0-0"]));
+        let restored: SpawnServerRequest = serde_json::from_value(json).unwrap();
+        assert_eq!(restored.anti_affinity, request.anti_affinity);
+    }
+
+    #[test]
+    fn test_server_label_selector() {
+        let mut info = ServerInfo::new(
+            "test-server".to_string(),
+            "127.0.0.1:8080".to_string(),
+            RegionCoordinate::center(),
+            RegionBounds::default(),
+            100,
+        );
+        info.labels.insert("env".to_string(), "prod".to_string());
+        info.labels.insert("gpu".to_string(), "true".to_string());
+
+        let selector = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+            pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+        };
+        assert!(info.matches_selector(&selector(&[("env", "prod")])));
+        assert!(info.matches_selector(&selector(&[])));
+        assert!(!info.matches_selector(&selector(&[("env", "staging")])));
+        assert!(!info.matches_selector(&selector(&[("env", "prod"), ("zone", "eu")])));
+
+        let json = serde_json::to_value(&info).unwrap();
+        assert_eq!(json["labels"]["gpu"], "true");
+        let mut legacy = json;
+        legacy.as_object_mut().unwrap().remove("labels");
+        let restored: ServerInfo = serde_json::from_value(legacy).unwrap();
+        assert!(restored.labels.is_empty());
+    }
+
     #[test]
     fn test_heartbeat_load() {
         let heartbeat = ServerHeartbeat::new(
@@ -351,6 +802,104 @@ mod tests {
             50,
             100,
         );
-        assert!((heartbeat.load - 0.5).abs() < 0.001);
+        assert!((heartbeat.load.get() - 0.5).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_infer_status() {
+        use std::time::Duration;
+
+        let fresh = ServerHeartbeat::new(ServerId::new(), ServerStatus::Stopped, 0, 100);
+        assert_eq!(infer_status(&fresh, Duration::from_secs(5)), InferredStatus::Starting);
+        assert_eq!(infer_status(&fresh, Duration::from_secs(600)), InferredStatus::Running);
+
+        let mut busy = ServerHeartbeat::new(ServerId::new(), ServerStatus::Starting, 40, 100);
+        busy.avg_tick_ms = 12.0;
+        assert_eq!(infer_status(&busy, Duration::from_secs(5)), InferredStatus::Running);
+
+        busy.avg_tick_ms = 80.0;
+        let degraded = infer_status(&busy, Duration::from_secs(600));
+        assert_eq!(degraded, InferredStatus::Degraded);
+        assert_eq!(degraded.status(), ServerStatus::Running);
+        busy.tick_budget_ms = 100.0;
+        assert_eq!(infer_status(&busy, Duration::from_secs(600)), InferredStatus::Running);
+
+        // The wire enum is unchanged, so older peers still parse every status.
+        assert!(serde_json::from_str::<ServerStatus>(r#""degraded""#).is_err());
+    }
+
+    #[test]
+    fn test_heartbeat_should_warn() {
+        let heartbeat = ServerHeartbeat::new(ServerId::new(), ServerStatus::Running, 90, 100);
+        assert!(heartbeat.should_warn(0.9));
+        assert!(!heartbeat.should_warn(0.95));
+        assert!(!ServerHeartbeat::new(ServerId::new(), ServerStatus::Running, 50, 100).should_warn(0.9));
+    }
+
+    #[test]
+    fn test_heartbeat_overrun_ratio() {
+        let mut heartbeat = ServerHeartbeat::new(ServerId::new(), ServerStatus::Running, 10, 100);
+        heartbeat.avg_tick_ms = 24.0;
+        assert_eq!(heartbeat.overrun_ratio(), 0.0);
+
+        heartbeat.tick_budget_ms = 16.0;
+        heartbeat.tick_overruns = 7;
+        assert!((heartbeat.overrun_ratio() - 1.5).abs() < 0.001);
+
+        let json = serde_json::to_value(&heartbeat).unwrap();
+        assert_eq!(json["tick_budget_ms"], 16.0);
+        assert_eq!(json["tick_overruns"], 7);
+
+        let mut legacy = json;
+        let fields = legacy.as_object_mut().unwrap();
+        fields.remove("tick_budget_ms");
+        fields.remove("tick_overruns");
+        let restored: ServerHeartbeat = serde_json::from_value(legacy).unwrap();
+        assert_eq!(restored.tick_overruns, 0);
+        assert_eq!(restored.overrun_ratio(), 0.0);
+    }
+
+    #[test]
+    fn test_load_clamping_and_nan() {
+        assert_eq!(Load::new(1.5).unwrap(), Load::FULL);
+        assert_eq!(Load::new(-0.2).unwrap(), Load::ZERO);
+        assert_eq!(Load::new(f32::NAN), Err(InvalidLoad));
+        assert_eq!(Load::from_ratio(5, 0), Load::ZERO);
+
+        assert_eq!(serde_json::to_string(&Load::new(0.25).unwrap()).unwrap(), "0.25");
+        assert_eq!(serde_json::from_str::<Load>("3.0").unwrap(), Load::FULL);
+    }
+
+    #[test]
+    fn test_load_ordering() {
+        let mut loads: Vec<Load> = [0.9, 0.1, 0.5, -0.0, 0.0]
+            .into_iter()
+            .map(|v| Load::new(v).unwrap())
+            .collect();
+        loads.sort();
+        let values: Vec<f32> = loads.into_iter().map(Load::get).collect();
+        assert_eq!(values, vec![0.0, 0.0, 0.1, 0.5, 0.9]);
+        assert!(Load::FULL > Load::new(0.99).unwrap());
+    }
+
+    #[test]
+    fn test_registration_region_occupied() {
+        let occupant = ServerId::from("horizon-1");
+        let response = RegistrationResponse::region_occupied(
+            ServerId::from("horizon-2"),
+            RegionCoordinate::new(1, 0, 0),
+            occupant.clone(),
+        );
+        assert!(!response.success);
+
+        let json = serde_json::to_string(&response).unwrap();
+        let restored: RegistrationResponse = serde_json::from_str(&json).unwrap();
+        match restored.failure_reason {
+            Some(RegistrationFailureReason::RegionOccupied { region, occupant: id }) => {
+                assert_eq!(region, RegionCoordinate::new(1, 0, 0));
+                assert_eq!(id, occupant);
+            }
+            other => panic!("Unexpected failure reason: {:?}", other),
+        }
     }
 }
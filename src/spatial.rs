@@ -4,6 +4,23 @@
 //! positions in the game world and define region boundaries.
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::grid::RegionOccupancy;
+
+/// One of the three spatial axes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+impl Axis {
+    /// All axes in X, Y, Z order.
+    pub const ALL: [Axis; 3] = [Axis::X, Axis::Y, Axis::Z];
+}
 
 /// 3D world coordinates using f64 for precision.
 ///
@@ -27,6 +44,24 @@ impl WorldCoordinate {
         Self::new(0.0, 0.0, 0.0)
     }
 
+    /// Get the component along the given axis.
+    pub fn component(&self, axis: Axis) -> f64 {
+        match axis {
+            Axis::X => self.x,
+            Axis::Y => self.y,
+            Axis::Z => self.z,
+        }
+    }
+
+    /// Set the component along the given axis.
+    pub fn set_component(&mut self, axis: Axis, value: f64) {
+        match axis {
+            Axis::X => self.x = value,
+            Axis::Y => self.y = value,
+            Axis::Z => self.z = value,
+        }
+    }
+
     /// Calculate 3D Euclidean distance to another coordinate.
     pub fn distance_to(&self, other: &WorldCoordinate) -> f64 {
         let dx = self.x - other.x;
@@ -81,6 +116,21 @@ impl WorldCoordinate {
         }
     }
 
+    /// Encodes as 24 little-endian bytes: x, y, z as IEEE 754 f64.
+    pub fn to_le_bytes(&self) -> [u8; 24] {
+        let mut bytes = [0u8; 24];
+        bytes[0..8].copy_from_slice(&self.x.to_le_bytes());
+        bytes[8..16].copy_from_slice(&self.y.to_le_bytes());
+        bytes[16..24].copy_from_slice(&self.z.to_le_bytes());
+        bytes
+    }
+
+    /// Decodes from the layout produced by [`WorldCoordinate::to_le_bytes`].
+    pub fn from_le_bytes(bytes: [u8; 24]) -> Self {
+        let field = |i: usize| f64::from_le_bytes(bytes[i * 8..(i + 1) * 8].try_into().unwrap());
+        Self::new(field(0), field(1), field(2))
+    }
+
     /// Creates from environment variables (HORIZON_CENTER_X/Y/Z).
     pub fn from_env() -> Self {
         let x = std::env::var("HORIZON_CENTER_X")
@@ -99,6 +149,17 @@ impl WorldCoordinate {
     }
 }
 
+/// Calculate the centroid (average position) of a set of coordinates.
+///
+/// Returns None for an empty slice.
+pub fn centroid(coords: &[WorldCoordinate]) -> Option<WorldCoordinate> {
+    if coords.is_empty() {
+        return None;
+    }
+    let sum = coords.iter().fold(WorldCoordinate::zero(), |acc, c| acc.add(c));
+    Some(sum.scale(1.0 / coords.len() as f64))
+}
+
 /// Server region coordinates (i64 for grid-based regions).
 ///
 /// This type represents a region's position in a discrete 3D grid,
@@ -121,11 +182,90 @@ impl RegionCoordinate {
         Self::new(0, 0, 0)
     }
 
+    /// Get the component along the given axis.
+    pub fn component(&self, axis: Axis) -> i64 {
+        match axis {
+            Axis::X => self.x,
+            Axis::Y => self.y,
+            Axis::Z => self.z,
+        }
+    }
+
+    /// Set the component along the given axis.
+    pub fn set_component(&mut self, axis: Axis, value: i64) {
+        match axis {
+            Axis::X => self.x = value,
+            Axis::Y => self.y = value,
+            Axis::Z => self.z = value,
+        }
+    }
+
     /// Calculate Manhattan distance to another region.
     pub fn manhattan_distance(&self, other: &RegionCoordinate) -> i64 {
         (self.x - other.x).abs() + (self.y - other.y).abs() + (self.z - other.z).abs()
     }
 
+    /// Calculate Chebyshev distance to another region.
+    ///
+    /// This is the number of steps needed when diagonal moves are allowed, so
+    /// all 26 surrounding regions are at distance 1.
+    pub fn chebyshev_distance(&self, other: &RegionCoordinate) -> i64 {
+        (self.x - other.x)
+            .abs()
+            .max((self.y - other.y).abs())
+            .max((self.z - other.z).abs())
+    }
+
+    /// Get every region at exactly Chebyshev distance `radius` from this one.
+    ///
+    /// Radius 0 yields this region alone; negative radii yield nothing.
+    /// Only the six faces of the cube are visited, and regions whose
+    /// coordinates would overflow `i64` are skipped.
+    pub fn shell(&self, radius: i64) -> Vec<RegionCoordinate> {
+        if radius < 0 {
+            return Vec::new();
+        }
+        if radius == 0 {
+            return vec![*self];
+        }
+        let r = radius;
+        let mut regions = Vec::new();
+        let mut push = |dx: i64, dy: i64, dz: i64| {
+            let (x, y, z) = (self.x.checked_add(dx), self.y.checked_add(dy), self.z.checked_add(dz));
+            if let (Some(x), Some(y), Some(z)) = (x, y, z) {
+                regions.push(RegionCoordinate::new(x, y, z));
+            }
+        };
+        // Both Z faces in full, the Y faces without their Z edges, then the X faces without either.
+        for dz in [-r, r] {
+            for dx in -r..=r {
+                for dy in -r..=r {
+                    push(dx, dy, dz);
+                }
+            }
+        }
+        for dy in [-r, r] {
+            for dx in -r..=r {
+                for dz in -r + 1..r {
+                    push(dx, dy, dz);
+                }
+            }
+        }
+        for dx in [-r, r] {
+            for dy in -r + 1..r {
+                for dz in -r + 1..r {
+                    push(dx, dy, dz);
+                }
+            }
+        }
+        regions
+    }
+
+    /// Iterate regions outward from this one, shell by shell, up to `max_radius`.
+    pub fn spiral(&self, max_radius: i64) -> impl Iterator<Item = RegionCoordinate> + '_ {
+        (0..=max_radius).flat_map(move |radius| self.shell(radius))
+    }
+
     /// Get adjacent region coordinates (6 directions in 3D).
     pub fn adjacent_regions(&self) -> Vec<RegionCoordinate> {
         vec![
@@ -138,6 +278,90 @@ impl RegionCoordinate {
         ]
     }
 
+    /// Get the sequence of face-adjacent regions from this region to `dest`.
+    ///
+    /// The path is monotone (never steps away from `dest`) and interleaves axis
+    /// steps in proportion to the distance along each axis, similar to a 3D
+    /// Bresenham line. Both endpoints are included.
+    pub fn path_to(&self, dest: &RegionCoordinate) -> Vec<RegionCoordinate> {
+        let delta = [dest.x - self.x, dest.y - self.y, dest.z - self.z];
+        let total = self.manhattan_distance(dest);
+        let mut taken = [0i64; 3];
+        let mut current = *self;
+        let mut path = Vec::with_capacity(total as usize + 1);
+        path.push(current);
+
+        for _ in 0..total {
+            // Step along the axis that is furthest behind its share of the line.
+            let axis = (0..3)
+                .filter(|&i| taken[i] < delta[i].abs())
+                .min_by(|&a, &b| {
+                    let progress = |i: usize| (2 * taken[i] + 1) as f64 / delta[i].abs() as f64;
+                    progress(a).total_cmp(&progress(b))
+                })
+                .expect("remaining steps must exist on some axis");
+            taken[axis] += 1;
+            let axis = Axis::ALL[axis];
+            current.set_component(axis, current.component(axis) + delta[axis as usize].signum());
+            path.push(current);
+        }
+        path
+    }
+
+    /// Get the regions this region splits into on a grid `factor` times finer.
+    ///
+    /// Child `(x * factor + i, y * factor + j, z * factor + k)` for each offset
+    /// in `0..factor`, so a factor of 2 yields 8 children. Returns an empty list
+    /// for factors below 1.
+    pub fn subdivide(&self, factor: i64) -> Vec<RegionCoordinate> {
+        let factor = factor.max(0);
+        let mut children = Vec::with_capacity((factor * factor * factor) as usize);
+        for i in 0..factor {
+            for j in 0..factor {
+                for k in 0..factor {
+                    children.push(RegionCoordinate::new(
+                        self.x * factor + i,
+                        self.y * factor + j,
+                        self.z * factor + k,
+                    ));
+                }
+            }
+        }
+        children
+    }
+
+    /// Returns true if `children` exactly tile this region on some finer grid.
+    ///
+    /// The children must be the result of [`RegionCoordinate::subdivide`] with a
+    /// factor of at least 2, in any order and without duplicates.
+    pub fn is_tiled_by(&self, children: &[RegionCoordinate]) -> bool {
+        self.check_tiling(children).is_ok()
+    }
+
+    /// Like [`RegionCoordinate::is_tiled_by`], but reports why `children` do not tile this region.
+    ///
+    /// The subdivision factor is taken from the cube root of the child count,
+    /// rounded to the nearest integer.
+    pub fn check_tiling(&self, children: &[RegionCoordinate]) -> Result<(), TilingError> {
+        let mut seen = HashSet::with_capacity(children.len());
+        if let Some(duplicate) = children.iter().find(|child| !seen.insert(**child)) {
+            return Err(TilingError::Overlap(*duplicate));
+        }
+        let factor = (children.len() as f64).cbrt().round() as i64;
+        if factor < 2 {
+            return Err(TilingError::TooFewChildren(children.len()));
+        }
+        let expected = self.subdivide(factor);
+        let expected_set: HashSet<_> = expected.iter().copied().collect();
+        if let Some(outside) = children.iter().find(|child| !expected_set.contains(child)) {
+            return Err(TilingError::OutsideParent(*outside));
+        }
+        match expected.into_iter().find(|child| !seen.contains(child)) {
+            Some(missing) => Err(TilingError::Gap(missing)),
+            None => Ok(()),
+        }
+    }
+
     /// Convert region coordinate to world coordinate center.
     ///
     /// Uses the region size to calculate the center point of this region.
@@ -158,6 +382,21 @@ impl RegionCoordinate {
         }
     }
 
+    /// Encodes as 24 little-endian bytes: x, y, z as two's-complement i64.
+    pub fn to_le_bytes(&self) -> [u8; 24] {
+        let mut bytes = [0u8; 24];
+        bytes[0..8].copy_from_slice(&self.x.to_le_bytes());
+        bytes[8..16].copy_from_slice(&self.y.to_le_bytes());
+        bytes[16..24].copy_from_slice(&self.z.to_le_bytes());
+        bytes
+    }
+
+    /// Decodes from the layout produced by [`RegionCoordinate::to_le_bytes`].
+    pub fn from_le_bytes(bytes: [u8; 24]) -> Self {
+        let field = |i: usize| i64::from_le_bytes(bytes[i * 8..(i + 1) * 8].try_into().unwrap());
+        Self::new(field(0), field(1), field(2))
+    }
+
     /// Creates from environment variables (HORIZON_REGION_X/Y/Z).
     pub fn from_env() -> Self {
         let x = std::env::var("HORIZON_REGION_X")
@@ -176,6 +415,266 @@ impl RegionCoordinate {
     }
 }
 
+/// Why a set of child regions does not tile a parent region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum TilingError {
+    /// A child region appears more than once
+    #[error("Child region ({}, {}, {}) appears more than once", .0.x, .0.y, .0.z)]
+    Overlap(RegionCoordinate),
+
+    /// A child region lies outside the parent
+    #[error("Child region ({}, {}, {}) lies outside the parent", .0.x, .0.y, .0.z)]
+    OutsideParent(RegionCoordinate),
+
+    /// Part of the parent is not covered by any child
+    #[error("Child region ({}, {}, {}) is missing", .0.x, .0.y, .0.z)]
+    Gap(RegionCoordinate),
+
+    /// Too few children for a subdivision factor of at least 2
+    #[error("{0} children cannot subdivide a region")]
+    TooFewChildren(usize),
+}
+
+/// Uniform grid of cubic regions covering the game world.
+///
+/// Bundles the region size so world/region conversions don't need it passed
+/// around separately. The region size is always positive and finite.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "RawWorldGrid")]
+pub struct WorldGrid {
+    /// Edge length of one region in world units
+    region_size: f64,
+    /// Incremented each time the grid is resized
+    #[serde(default)]
+    grid_generation: u64,
+}
+
+/// Error returned when a region size is zero, negative, infinite or NaN.
+#[derive(Debug, Clone, Copy, PartialEq, thiserror::Error)]
+#[error("Region size must be positive and finite, got {0}")]
+pub struct InvalidRegionSize(pub f64);
+
+#[derive(Deserialize)]
+struct RawWorldGrid {
+    region_size: f64,
+    #[serde(default)]
+    grid_generation: u64,
+}
+
+impl TryFrom<RawWorldGrid> for WorldGrid {
+    type Error = InvalidRegionSize;
+
+    fn try_from(raw: RawWorldGrid) -> Result<Self, Self::Error> {
+        Self::with_generation(raw.region_size, raw.grid_generation)
+    }
+}
+
+impl WorldGrid {
+    /// Creates a grid with the given region size.
+    pub fn new(region_size: f64) -> Result<Self, InvalidRegionSize> {
+        Self::with_generation(region_size, 0)
+    }
+
+    /// Restores a grid that has already been resized `grid_generation` times.
+    pub fn with_generation(region_size: f64, grid_generation: u64) -> Result<Self, InvalidRegionSize> {
+        Self::check_region_size(region_size)?;
+        Ok(Self {
+            region_size,
+            grid_generation,
+        })
+    }
+
+    fn check_region_size(region_size: f64) -> Result<(), InvalidRegionSize> {
+        if !region_size.is_finite() || region_size <= 0.0 {
+            return Err(InvalidRegionSize(region_size));
+        }
+        Ok(())
+    }
+
+    /// Edge length of one region in world units.
+    pub fn region_size(&self) -> f64 {
+        self.region_size
+    }
+
+    /// Number of times the grid has been resized.
+    pub fn grid_generation(&self) -> u64 {
+        self.grid_generation
+    }
+
+    /// Changes the region size, invalidating previously derived regions.
+    ///
+    /// An invalid size leaves the grid and its generation unchanged.
+    pub fn resize(&mut self, region_size: f64) -> Result<(), InvalidRegionSize> {
+        Self::check_region_size(region_size)?;
+        self.region_size = region_size;
+        self.grid_generation += 1;
+        Ok(())
+    }
+
+    /// Get the region containing a world coordinate.
+    pub fn region_of(&self, coord: &WorldCoordinate) -> RegionCoordinate {
+        RegionCoordinate::from_world_coordinate(coord, self.region_size)
+    }
+
+    /// Get the region containing a world coordinate, stamped with the grid generation.
+    pub fn stamped_region_of(&self, coord: &WorldCoordinate) -> StampedRegion {
+        StampedRegion {
+            coord: self.region_of(coord),
+            generation: self.grid_generation,
+        }
+    }
+
+    /// Snap a coordinate to the nearest region center.
+    ///
+    /// Region centers are the points returned by [`RegionCoordinate::to_world_center`].
+    pub fn snap_to_region_center(&self, coord: &WorldCoordinate) -> WorldCoordinate {
+        self.snap_to_grid(coord, self.region_size)
+    }
+
+    /// Snap a coordinate to the nearest node of a lattice with the given spacing.
+    ///
+    /// Non-positive spacings leave the coordinate unchanged.
+    pub fn snap_to_grid(&self, coord: &WorldCoordinate, spacing: f64) -> WorldCoordinate {
+        if spacing <= 0.0 {
+            return *coord;
+        }
+        let snap = |v: f64| (v / spacing).round() * spacing;
+        WorldCoordinate::new(snap(coord.x), snap(coord.y), snap(coord.z))
+    }
+
+    /// Render one Z layer of an occupancy map as ASCII, for logs.
+    ///
+    /// The first line describes the layer; each following line is one row of
+    /// regions with Y decreasing downwards and X increasing to the right.
+    /// Occupied regions are drawn as `#` and empty ones as `.`.
+    pub fn debug_ascii(&self, occupancy: &RegionOccupancy, layer_z: i64) -> String {
+        let dims = occupancy.dims();
+        let mut out = format!(
+            "z={} x={}..={} y={}..={} region_size={}\n",
+            layer_z, dims.min.x, dims.max.x, dims.min.y, dims.max.y, self.region_size
+        );
+        for y in (dims.min.y..=dims.max.y).rev() {
+            for x in dims.min.x..=dims.max.x {
+                let occupied = occupancy.is_occupied(&RegionCoordinate::new(x, y, layer_z));
+                out.push(if occupied { '#' } else { '.' });
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Region coordinate tagged with the grid generation it was derived from.
+///
+/// Region coordinates are only meaningful for the region size they were
+/// computed with; caching a `StampedRegion` instead of a bare coordinate lets
+/// the holder notice when the grid has been resized since.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct StampedRegion {
+    /// Region coordinate
+    pub coord: RegionCoordinate,
+    /// `WorldGrid::grid_generation` at the time of derivation
+    pub generation: u64,
+}
+
+impl StampedRegion {
+    /// Returns true if `grid` has been resized since this region was derived.
+    pub fn is_stale(&self, grid: &WorldGrid) -> bool {
+        self.generation != grid.grid_generation
+    }
+}
+
+/// Floating origin for rendering large worlds.
+///
+/// Engines that render in f32 lose precision far from the origin, so clients
+/// periodically move the origin to the player's position and render everything
+/// relative to it. World coordinates stay in f64 and are unaffected.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct FloatingOrigin {
+    /// World position of the render origin
+    pub offset: WorldCoordinate,
+}
+
+impl FloatingOrigin {
+    /// Creates a floating origin at the given world position.
+    pub fn new(offset: WorldCoordinate) -> Self {
+        Self { offset }
+    }
+
+    /// Convert a world coordinate to the render frame.
+    pub fn to_render(&self, world: &WorldCoordinate) -> WorldCoordinate {
+        self.offset.vector_to(world)
+    }
+
+    /// Convert a render-frame coordinate back to world space.
+    pub fn to_world(&self, render: &WorldCoordinate) -> WorldCoordinate {
+        self.offset.add(render)
+    }
+
+    /// Move the origin to a new world position.
+    pub fn rebase(&mut self, new_offset: WorldCoordinate) {
+        self.offset = new_offset;
+    }
+}
+
+/// Maximum extent of the world along every axis.
+///
+/// Positions outside these limits indicate a bug or a cheating client; Horizon
+/// validates incoming positions against them.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "RawWorldLimits")]
+pub struct WorldLimits {
+    /// Largest absolute value allowed on any axis
+    max_coordinate: f64,
+}
+
+/// Error returned when a world limit is negative, infinite or NaN.
+#[derive(Debug, Clone, Copy, PartialEq, thiserror::Error)]
+#[error("World limit must be finite and non-negative, got {0}")]
+pub struct InvalidWorldLimits(pub f64);
+
+#[derive(Deserialize)]
+struct RawWorldLimits {
+    max_coordinate: f64,
+}
+
+impl TryFrom<RawWorldLimits> for WorldLimits {
+    type Error = InvalidWorldLimits;
+
+    fn try_from(raw: RawWorldLimits) -> Result<Self, Self::Error> {
+        Self::new(raw.max_coordinate)
+    }
+}
+
+impl WorldLimits {
+    /// Creates limits with the given maximum absolute coordinate.
+    pub fn new(max_coordinate: f64) -> Result<Self, InvalidWorldLimits> {
+        if !max_coordinate.is_finite() || max_coordinate < 0.0 {
+            return Err(InvalidWorldLimits(max_coordinate));
+        }
+        Ok(Self { max_coordinate })
+    }
+
+    /// Largest absolute value allowed on any axis.
+    pub fn max_coordinate(&self) -> f64 {
+        self.max_coordinate
+    }
+
+    /// Returns true if every axis is within the limits (NaN never is).
+    pub fn is_within(&self, coord: &WorldCoordinate) -> bool {
+        [coord.x, coord.y, coord.z]
+            .iter()
+            .all(|v| v.abs() <= self.max_coordinate)
+    }
+
+    /// Clamps each axis into `[-max_coordinate, max_coordinate]`, mapping NaN axes to 0.0.
+    pub fn clamp_to_world(&self, coord: &WorldCoordinate) -> WorldCoordinate {
+        let max = self.max_coordinate;
+        let clamp = |v: f64| if v.is_nan() { 0.0 } else { v.clamp(-max, max) };
+        WorldCoordinate::new(clamp(coord.x), clamp(coord.y), clamp(coord.z))
+    }
+}
+
 /// Defines the spatial boundaries of a game region.
 ///
 /// This structure defines a 3D axis-aligned bounding box (AABB) that encompasses
@@ -241,6 +740,23 @@ impl RegionBounds {
         (self.max_x - self.min_x) / 2.0
     }
 
+    /// Scale these bounds about their center.
+    ///
+    /// A factor of 1.0 returns the same bounds and 2.0 doubles each dimension.
+    /// Negative factors are treated as 0.0, collapsing the bounds to the center.
+    pub fn scale_from_center(&self, factor: f64) -> RegionBounds {
+        let factor = factor.max(0.0);
+        let center = self.center();
+        let hx = (self.max_x - self.min_x) / 2.0 * factor;
+        let hy = (self.max_y - self.min_y) / 2.0 * factor;
+        let hz = (self.max_z - self.min_z) / 2.0 * factor;
+        RegionBounds::new(
+            center.x - hx, center.x + hx,
+            center.y - hy, center.y + hy,
+            center.z - hz, center.z + hz,
+        )
+    }
+
     /// Check if a world coordinate is within these bounds.
     pub fn contains(&self, coord: &WorldCoordinate) -> bool {
         coord.x >= self.min_x && coord.x <= self.max_x &&
@@ -263,6 +779,104 @@ impl RegionBounds {
         self.min_y <= other.max_y && self.max_y >= other.min_y &&
         self.min_z <= other.max_z && self.max_z >= other.min_z
     }
+
+    /// Get the eight corners of the box.
+    ///
+    /// Corner `i` takes the max X if bit 0 of `i` is set, max Y if bit 1 is
+    /// set and max Z if bit 2 is set, so index 0 is the min corner and index 7
+    /// the max corner.
+    pub fn corners(&self) -> [WorldCoordinate; 8] {
+        std::array::from_fn(|i| {
+            WorldCoordinate::new(
+                if i & 1 == 0 { self.min_x } else { self.max_x },
+                if i & 2 == 0 { self.min_y } else { self.max_y },
+                if i & 4 == 0 { self.min_z } else { self.max_z },
+            )
+        })
+    }
+}
+
+/// Serde `with` module encoding [`RegionBounds`] as center plus half-extents.
+///
+/// Serializes as `{"center":[x,y,z],"half_extent":[hx,hy,hz]}` instead of the
+/// default six named fields, which keeps topology snapshots with many regions
+/// small. Use with `#[serde(with = "bounds_center_extent")]`.
+pub mod bounds_center_extent {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::RegionBounds;
+
+    #[derive(Serialize, Deserialize)]
+    struct CenterExtent {
+        center: [f64; 3],
+        half_extent: [f64; 3],
+    }
+
+    pub fn serialize<S: Serializer>(bounds: &RegionBounds, serializer: S) -> Result<S::Ok, S::Error> {
+        let center = bounds.center();
+        CenterExtent {
+            center: [center.x, center.y, center.z],
+            half_extent: [
+                (bounds.max_x - bounds.min_x) / 2.0,
+                (bounds.max_y - bounds.min_y) / 2.0,
+                (bounds.max_z - bounds.min_z) / 2.0,
+            ],
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<RegionBounds, D::Error> {
+        let CenterExtent { center: c, half_extent: h } = CenterExtent::deserialize(deserializer)?;
+        Ok(RegionBounds::new(c[0] - h[0], c[0] + h[0], c[1] - h[1], c[1] + h[1], c[2] - h[2], c[2] + h[2]))
+    }
+}
+
+/// Serde `with` module encoding [`RegionCoordinate`] as an `[x, y, z]` array.
+///
+/// Smaller than the default `{"x":..,"y":..,"z":..}` map form and matches what
+/// most external tools expect. Use with `#[serde(with = "region_coord_array")]`.
+pub mod region_coord_array {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::RegionCoordinate;
+
+    pub fn serialize<S: Serializer>(coord: &RegionCoordinate, serializer: S) -> Result<S::Ok, S::Error> {
+        [coord.x, coord.y, coord.z].serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<RegionCoordinate, D::Error> {
+        let [x, y, z] = <[i64; 3]>::deserialize(deserializer)?;
+        Ok(RegionCoordinate::new(x, y, z))
+    }
+}
+
+/// Number of decimal places kept by [`world_coord_rounded`].
+pub const COORDINATE_DECIMALS: i32 = 3;
+
+/// Serde `with` module that rounds [`WorldCoordinate`] components on serialize.
+///
+/// Components are rounded to [`COORDINATE_DECIMALS`] decimal places, which
+/// shrinks JSON payloads at the cost of precision: with the default of 3 the
+/// serialized position may be off by up to 0.0005 world units per axis.
+/// Deserialization accepts full-precision values unchanged.
+/// Use with `#[serde(with = "world_coord_rounded")]`.
+pub mod world_coord_rounded {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{WorldCoordinate, COORDINATE_DECIMALS};
+
+    fn round(value: f64) -> f64 {
+        let factor = 10f64.powi(COORDINATE_DECIMALS);
+        (value * factor).round() / factor
+    }
+
+    pub fn serialize<S: Serializer>(coord: &WorldCoordinate, serializer: S) -> Result<S::Ok, S::Error> {
+        WorldCoordinate::new(round(coord.x), round(coord.y), round(coord.z)).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<WorldCoordinate, D::Error> {
+        WorldCoordinate::deserialize(deserializer)
+    }
 }
 
 #[cfg(test)]
@@ -276,6 +890,58 @@ mod tests {
         assert!((a.distance_to(&b) - 5.0).abs() < 0.0001);
     }
 
+    #[test]
+    fn test_world_grid_snapping() {
+        let grid = WorldGrid::new(100.0).unwrap();
+        let snapped = grid.snap_to_region_center(&WorldCoordinate::new(140.0, -60.0, 249.0));
+        assert_eq!(snapped, WorldCoordinate::new(100.0, -100.0, 200.0));
+        assert_eq!(snapped, RegionCoordinate::new(1, -1, 2).to_world_center(100.0));
+
+        let node = grid.snap_to_grid(&WorldCoordinate::new(12.4, 17.6, -2.6), 5.0);
+        assert_eq!(node, WorldCoordinate::new(10.0, 20.0, -5.0));
+        let unchanged = WorldCoordinate::new(1.1, 2.2, 3.3);
+        assert_eq!(grid.snap_to_grid(&unchanged, 0.0), unchanged);
+    }
+
+    #[test]
+    fn test_centroid() {
+        let points = [
+            WorldCoordinate::new(0.0, 0.0, 0.0),
+            WorldCoordinate::new(4.0, 0.0, -2.0),
+            WorldCoordinate::new(2.0, 6.0, 2.0),
+        ];
+        assert_eq!(centroid(&points), Some(WorldCoordinate::new(2.0, 2.0, 0.0)));
+        assert_eq!(centroid(&[]), None);
+    }
+
+    #[test]
+    fn test_world_limits() {
+        let limits = WorldLimits::new(1000.0).unwrap();
+        assert!(limits.is_within(&WorldCoordinate::new(1000.0, -1000.0, 0.0)));
+        assert!(!limits.is_within(&WorldCoordinate::new(0.0, 1000.5, 0.0)));
+        assert!(!limits.is_within(&WorldCoordinate::new(f64::NAN, 0.0, 0.0)));
+        assert!(!limits.is_within(&WorldCoordinate::new(f64::INFINITY, 0.0, 0.0)));
+
+        let clamped = limits.clamp_to_world(&WorldCoordinate::new(5000.0, -20.0, -1e9));
+        assert_eq!(clamped, WorldCoordinate::new(1000.0, -20.0, -1000.0));
+        assert!(limits.is_within(&clamped));
+
+        let clamped = limits.clamp_to_world(&WorldCoordinate::new(f64::NAN, f64::INFINITY, 3.0));
+        assert_eq!(clamped, WorldCoordinate::new(0.0, 1000.0, 3.0));
+    }
+
+    #[test]
+    fn test_invalid_world_limits() {
+        assert_eq!(WorldLimits::new(-1.0), Err(InvalidWorldLimits(-1.0)));
+        assert!(WorldLimits::new(f64::NAN).is_err());
+        assert!(WorldLimits::new(f64::INFINITY).is_err());
+        assert!(serde_json::from_str::<WorldLimits>(r#"{"max_coordinate": -1}"#).is_err());
+
+        let limits: WorldLimits = serde_json::from_str(r#"{"max_coordinate": 0}"#).unwrap();
+        assert_eq!(limits.max_coordinate(), 0.0);
+        assert_eq!(limits.clamp_to_world(&WorldCoordinate::new(5.0, -5.0, 0.0)), WorldCoordinate::zero());
+    }
+
     #[test]
     fn test_region_bounds_contains() {
         let bounds = RegionBounds::from_center(WorldCoordinate::zero(), 100.0);
@@ -284,10 +950,245 @@ mod tests {
         assert!(!bounds.contains(&WorldCoordinate::new(101.0, 0.0, 0.0)));
     }
 
+    #[test]
+    fn test_region_bounds_scale_from_center() {
+        let bounds = RegionBounds::new(0.0, 10.0, 0.0, 20.0, -5.0, 5.0);
+        assert_eq!(bounds.scale_from_center(1.0), bounds);
+        assert_eq!(bounds.scale_from_center(2.0), RegionBounds::new(-5.0, 15.0, -10.0, 30.0, -10.0, 10.0));
+        assert_eq!(bounds.scale_from_center(0.5), RegionBounds::new(2.5, 7.5, 5.0, 15.0, -2.5, 2.5));
+        let collapsed = bounds.scale_from_center(-1.0);
+        assert_eq!(collapsed.min_x, collapsed.max_x);
+        assert_eq!(collapsed.center(), bounds.center());
+    }
+
     #[test]
     fn test_region_coordinate_conversion() {
         let world = WorldCoordinate::new(150.0, 50.0, -25.0);
         let region = RegionCoordinate::from_world_coordinate(&world, 100.0);
         assert_eq!(region, RegionCoordinate::new(1, 0, -1));
     }
+
+    #[test]
+    fn test_region_path_to() {
+        let start = RegionCoordinate::new(0, 0, 0);
+        let dest = RegionCoordinate::new(3, -2, 1);
+        let path = start.path_to(&dest);
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&dest));
+        assert_eq!(path.len() as i64, start.manhattan_distance(&dest) + 1);
+        for pair in path.windows(2) {
+            assert_eq!(pair[0].manhattan_distance(&pair[1]), 1);
+        }
+        assert_eq!(start.path_to(&start), vec![start]);
+    }
+
+    #[test]
+    fn test_bounds_center_extent_round_trip() {
+        #[derive(Serialize, Deserialize)]
+        struct Compact {
+            #[serde(with = "bounds_center_extent")]
+            bounds: RegionBounds,
+        }
+
+        let bounds = RegionBounds::new(-10.0, 30.0, 0.0, 8.0, -4.0, 4.0);
+        let compact = serde_json::to_value(Compact { bounds }).unwrap();
+        assert_eq!(compact["bounds"]["center"], serde_json::json!([10.0, 4.0, 0.0]));
+        assert_eq!(compact["bounds"]["half_extent"], serde_json::json!([20.0, 4.0, 4.0]));
+
+        let restored: Compact = serde_json::from_value(compact).unwrap();
+        let verbose = serde_json::to_string(&restored.bounds).unwrap();
+        assert_eq!(serde_json::from_str::<RegionBounds>(&verbose).unwrap(), bounds);
+    }
+
+    #[test]
+    fn test_region_bounds_corners() {
+        let bounds = RegionBounds::new(-1.0, 3.0, 0.0, 10.0, -5.0, 5.0);
+        let corners = bounds.corners();
+        assert_eq!(corners[0], WorldCoordinate::new(-1.0, 0.0, -5.0));
+        assert_eq!(corners[1], WorldCoordinate::new(3.0, 0.0, -5.0));
+        assert_eq!(corners[6], WorldCoordinate::new(-1.0, 10.0, 5.0));
+        assert_eq!(corners[7], WorldCoordinate::new(3.0, 10.0, 5.0));
+        for (i, a) in corners.iter().enumerate() {
+            assert!(corners[i + 1..].iter().all(|b| a != b));
+        }
+        assert_eq!(centroid(&corners), Some(bounds.center()));
+    }
+
+    #[test]
+    fn test_region_shells() {
+        let origin = RegionCoordinate::new(2, -1, 0);
+        assert_eq!(origin.shell(0), vec![origin]);
+        assert_eq!(origin.shell(1).len(), 26);
+        assert_eq!(origin.shell(2).len(), 98);
+        assert!(origin.shell(-1).is_empty());
+        assert!(origin.shell(2).iter().all(|r| r.chebyshev_distance(&origin) == 2));
+        let shell: HashSet<_> = origin.shell(3).into_iter().collect();
+        assert_eq!(shell.len(), 7usize.pow(3) - 5usize.pow(3));
+
+        let edge = RegionCoordinate::new(i64::MAX, 0, 0);
+        assert_eq!(edge.shell(1).len(), 17);
+
+        let spiral: Vec<_> = origin.spiral(2).collect();
+        assert_eq!(spiral.len(), 125);
+        assert_eq!(spiral[0], origin);
+        assert!(spiral.windows(2).all(|w| {
+            w[0].chebyshev_distance(&origin) <= w[1].chebyshev_distance(&origin)
+        }));
+    }
+
+    #[test]
+    fn test_region_tiling() {
+        let parent = RegionCoordinate::new(1, -1, 0);
+        let mut children = parent.subdivide(2);
+        assert_eq!(children.len(), 8);
+        assert!(children.contains(&RegionCoordinate::new(2, -2, 0)));
+        assert!(children.contains(&RegionCoordinate::new(3, -1, 1)));
+        children.reverse();
+        assert!(parent.is_tiled_by(&children));
+        assert!(parent.is_tiled_by(&parent.subdivide(3)));
+
+        let mut duplicate = children.clone();
+        duplicate[0] = duplicate[1];
+        assert!(!parent.is_tiled_by(&duplicate));
+        assert!(!parent.is_tiled_by(&children[..7]));
+        assert!(!parent.is_tiled_by(&RegionCoordinate::center().subdivide(2)));
+        assert!(!parent.is_tiled_by(&parent.subdivide(1)));
+
+        assert_eq!(parent.check_tiling(&duplicate), Err(TilingError::Overlap(duplicate[0])));
+        assert_eq!(parent.check_tiling(&children[1..]), Err(TilingError::Gap(children[0])));
+        let mut outside = children.clone();
+        outside[0] = RegionCoordinate::new(9, 9, 9);
+        assert_eq!(parent.check_tiling(&outside), Err(TilingError::OutsideParent(outside[0])));
+        assert_eq!(parent.check_tiling(&parent.subdivide(1)), Err(TilingError::TooFewChildren(1)));
+    }
+
+    #[test]
+    fn test_stamped_region_staleness() {
+        let mut grid = WorldGrid::new(1000.0).unwrap();
+        let position = WorldCoordinate::new(1500.0, 0.0, 0.0);
+        let cached = grid.stamped_region_of(&position);
+        assert_eq!(cached.coord, RegionCoordinate::new(1, 0, 0));
+        assert!(!cached.is_stale(&grid));
+
+        grid.resize(500.0).unwrap();
+        assert!(cached.is_stale(&grid));
+        let fresh = grid.stamped_region_of(&position);
+        assert_eq!(fresh.coord, RegionCoordinate::new(3, 0, 0));
+        assert!(!fresh.is_stale(&grid));
+
+        let legacy: WorldGrid = serde_json::from_str(r#"{"region_size":250.0}"#).unwrap();
+        assert_eq!(legacy.grid_generation(), 0);
+        assert_eq!(legacy.region_size(), 250.0);
+    }
+
+    #[test]
+    fn test_invalid_region_size() {
+        assert_eq!(WorldGrid::new(0.0), Err(InvalidRegionSize(0.0)));
+        assert!(WorldGrid::new(-10.0).is_err());
+        assert!(WorldGrid::new(f64::NAN).is_err());
+        assert!(WorldGrid::with_generation(f64::INFINITY, 3).is_err());
+        assert!(serde_json::from_str::<WorldGrid>(r#"{"region_size":0.0}"#).is_err());
+
+        let mut grid = WorldGrid::new(1000.0).unwrap();
+        assert_eq!(grid.resize(-1.0), Err(InvalidRegionSize(-1.0)));
+        assert_eq!(grid.region_size(), 1000.0);
+        assert_eq!(grid.grid_generation(), 0);
+    }
+
+    #[test]
+    fn test_debug_ascii() {
+        use crate::grid::RegionDims;
+
+        let dims = RegionDims::new(RegionCoordinate::new(0, 0, 0), RegionCoordinate::new(2, 1, 1));
+        let mut occupancy = RegionOccupancy::new(dims).unwrap();
+        occupancy.set(&RegionCoordinate::new(0, 0, 0), true);
+        occupancy.set(&RegionCoordinate::new(2, 1, 0), true);
+        occupancy.set(&RegionCoordinate::new(1, 1, 1), true);
+
+        let grid = WorldGrid::new(1000.0).unwrap();
+        assert_eq!(
+            grid.debug_ascii(&occupancy, 0),
+            "z=0 x=0..=2 y=0..=1 region_size=1000\n..#\n#..\n"
+        );
+        assert!(grid.debug_ascii(&occupancy, 1).ends_with(".#.\n...\n"));
+        assert!(grid.debug_ascii(&occupancy, 5).ends_with("...\n...\n"));
+    }
+
+    #[test]
+    fn test_floating_origin() {
+        let far = WorldCoordinate::new(5_000_000.0, 120.0, -8_000_000.0);
+        let mut origin = FloatingOrigin::default();
+        assert_eq!(origin.to_render(&far), far);
+
+        origin.rebase(WorldCoordinate::new(5_000_000.0, 0.0, -8_000_000.0));
+        let player = WorldCoordinate::new(5_000_001.25, 120.0, -7_999_998.5);
+        let render = origin.to_render(&player);
+        assert_eq!(render, WorldCoordinate::new(1.25, 120.0, 1.5));
+        assert!((render.x as f32 - 1.25).abs() < 1e-6);
+        assert_eq!(origin.to_world(&render), player);
+    }
+
+    #[test]
+    fn test_region_coord_array_round_trip() {
+        #[derive(Serialize, Deserialize)]
+        struct Compact {
+            #[serde(with = "region_coord_array")]
+            region: RegionCoordinate,
+        }
+
+        let region = RegionCoordinate::new(-3, 0, 12);
+        let compact = serde_json::to_value(Compact { region }).unwrap();
+        assert_eq!(compact["region"], serde_json::json!([-3, 0, 12]));
+
+        let restored: Compact = serde_json::from_value(compact).unwrap();
+        let map_form = serde_json::to_value(restored.region).unwrap();
+        assert_eq!(map_form, serde_json::json!({"x": -3, "y": 0, "z": 12}));
+        assert_eq!(serde_json::from_value::<RegionCoordinate>(map_form).unwrap(), region);
+    }
+
+    #[test]
+    fn test_world_coord_rounded() {
+        #[derive(Serialize, Deserialize)]
+        struct Position {
+            #[serde(with = "world_coord_rounded")]
+            pos: WorldCoordinate,
+        }
+
+        let json = serde_json::to_string(&Position {
+            pos: WorldCoordinate::new(1.234567, -9.87654321, 100.0),
+        })
+        .unwrap();
+        assert_eq!(json, r#"{"pos":{"x":1.235,"y":-9.877,"z":100.0}}"#);
+
+        let restored: Position = serde_json::from_str(r#"{"pos":{"x":1.234567,"y":2.5,"z":0.0}}"#).unwrap();
+        assert_eq!(restored.pos.x, 1.234567);
+    }
+
+    #[test]
+    fn test_coordinate_le_bytes() {
+        let world = WorldCoordinate::new(1.5, -2.25, 1e12);
+        assert_eq!(WorldCoordinate::from_le_bytes(world.to_le_bytes()), world);
+        assert_eq!(&world.to_le_bytes()[0..8], &[0, 0, 0, 0, 0, 0, 0xf8, 0x3f]);
+
+        let region = RegionCoordinate::new(1, -1, i64::MAX);
+        let bytes = region.to_le_bytes();
+        assert_eq!(RegionCoordinate::from_le_bytes(bytes), region);
+        assert_eq!(&bytes[0..8], &[1, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(&bytes[8..16], &[0xff; 8]);
+        assert_eq!(&bytes[16..24], &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f]);
+    }
+
+    #[test]
+    fn test_component_by_axis() {
+        let mut world = WorldCoordinate::new(1.0, 2.0, 3.0);
+        let mut region = RegionCoordinate::new(4, 5, 6);
+        for (i, axis) in Axis::ALL.into_iter().enumerate() {
+            assert_eq!(world.component(axis), (i + 1) as f64);
+            assert_eq!(region.component(axis), (i + 4) as i64);
+            world.set_component(axis, -1.0);
+            region.set_component(axis, -1);
+        }
+        assert_eq!(world, WorldCoordinate::new(-1.0, -1.0, -1.0));
+        assert_eq!(region, RegionCoordinate::new(-1, -1, -1));
+    }
 }
@@ -4,11 +4,15 @@
 //! when they move across region boundaries.
 
 use serde::{Deserialize, Serialize};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use crate::player::PlayerId;
+use crate::codec;
+use crate::player::{PlayerId, PlayerState};
 use crate::server::ServerId;
-use crate::spatial::WorldCoordinate;
+use crate::spatial::{RegionCoordinate, WorldCoordinate};
+use crate::units::Millis;
+use crate::util::Backoff;
 
 /// Transfer token that authorizes a player to connect to a new server.
 ///
@@ -139,6 +143,173 @@ impl TransferToken {
     }
 }
 
+/// Transfer token authorizing a whole batch of players at once.
+///
+/// Used when a region's players are migrated together (for example during a
+/// region handoff), so the target validates one token instead of one per player.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchTransferToken {
+    /// Unique token identifier
+    pub token_id: String,
+    /// Source server
+    pub from: ServerId,
+    /// Target server
+    pub to: ServerId,
+    /// Players covered by this token
+    pub player_ids: Vec<PlayerId>,
+    /// When this token expires (ms since epoch)
+    pub expires_at_ms: u64,
+    /// Cryptographic signature (HMAC or similar)
+    pub signature: String,
+}
+
+impl BatchTransferToken {
+    /// Creates a new batch token valid for the default token duration.
+    pub fn new(from: ServerId, to: ServerId, player_ids: Vec<PlayerId>, secret_key: &[u8]) -> Self {
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+
+        let mut token = Self {
+            token_id: format!("btxfr-{}-{}", now_ms, TransferToken::rand_component()),
+            from,
+            to,
+            player_ids,
+            expires_at_ms: now_ms + DEFAULT_TOKEN_VALIDITY_SECS * 1000,
+            signature: String::new(),
+        };
+        token.signature = token.compute_signature(secret_key);
+        token
+    }
+
+    /// Returns true if the player is covered by this token.
+    pub fn contains(&self, player_id: &PlayerId) -> bool {
+        self.player_ids.contains(player_id)
+    }
+
+    /// Returns true if the token has expired.
+    pub fn is_expired(&self) -> bool {
+        let now_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        now_ms > self.expires_at_ms
+    }
+
+    /// Verifies the token signature and expiration.
+    pub fn verify(&self, secret_key: &[u8]) -> Result<(), TransferError> {
+        if self.is_expired() {
+            return Err(TransferError::TokenExpired);
+        }
+        if self.signature != self.compute_signature(secret_key) {
+            return Err(TransferError::InvalidSignature);
+        }
+        Ok(())
+    }
+
+    /// Computes signature for this token.
+    fn compute_signature(&self, key: &[u8]) -> String {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        let mut hasher = DefaultHasher::new();
+        self.token_id.hash(&mut hasher);
+        self.from.hash(&mut hasher);
+        self.to.hash(&mut hasher);
+        self.player_ids.hash(&mut hasher);
+        self.expires_at_ms.hash(&mut hasher);
+        key.hash(&mut hasher);
+        format!("{:016x}", hasher.finish())
+    }
+}
+
+/// Current [`TransferEnvelope`] format version.
+pub const TRANSFER_ENVELOPE_VERSION: u16 = 1;
+
+/// Largest encoded player state [`TransferEnvelope::decode`] accepts, after decompression.
+pub const MAX_TRANSFER_PAYLOAD_BYTES: usize = 4 * 1024 * 1024;
+
+/// Versioned, checksummed container for a player state in transit.
+///
+/// The payload is the JSON-encoded `PlayerState`, DEFLATE-compressed when
+/// `compressed` is set (requires the `compression` feature). The checksum
+/// covers the uncompressed JSON so corruption is caught after decompression.
+/// In JSON the payload is a base64 string.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferEnvelope {
+    /// Envelope format version
+    pub version: u16,
+    /// Whether the payload is compressed
+    pub compressed: bool,
+    /// Checksum of the uncompressed payload
+    pub checksum: u64,
+    /// Encoded player state
+    #[serde(with = "codec::base64_bytes")]
+    pub payload: Vec<u8>,
+}
+
+impl TransferEnvelope {
+    /// Encodes a player state, compressing it if requested and supported.
+    ///
+    /// Without the `compression` feature the payload is always stored uncompressed.
+    pub fn encode(state: &PlayerState, compress: bool) -> Result<Self, TransferError> {
+        let json = serde_json::to_vec(state)
+            .map_err(|e| TransferError::SerializationError(e.to_string()))?;
+        let checksum = codec::checksum(&json);
+
+        #[cfg(feature = "compression")]
+        if compress {
+            let payload = codec::compress(&json)
+                .map_err(|e| TransferError::SerializationError(e.to_string()))?;
+            return Ok(Self { version: TRANSFER_ENVELOPE_VERSION, compressed: true, checksum, payload });
+        }
+        #[cfg(not(feature = "compression"))]
+        let _ = compress;
+
+        Ok(Self { version: TRANSFER_ENVELOPE_VERSION, compressed: false, checksum, payload: json })
+    }
+
+    /// Decodes the player state, verifying version and checksum.
+    ///
+    /// Payloads larger than [`MAX_TRANSFER_PAYLOAD_BYTES`] are rejected.
+    pub fn decode(&self) -> Result<PlayerState, TransferError> {
+        self.decode_limited(MAX_TRANSFER_PAYLOAD_BYTES)
+    }
+
+    /// Decodes the player state, rejecting payloads over `max_len` bytes once decompressed.
+    pub fn decode_limited(&self, max_len: usize) -> Result<PlayerState, TransferError> {
+        if self.version != TRANSFER_ENVELOPE_VERSION {
+            return Err(TransferError::UnsupportedVersion(self.version));
+        }
+
+        let json = if self.compressed {
+            #[cfg(feature = "compression")]
+            {
+                codec::decompress(&self.payload, max_len)
+                    .map_err(|e| TransferError::SerializationError(e.to_string()))?
+            }
+            #[cfg(not(feature = "compression"))]
+            {
+                return Err(TransferError::SerializationError(
+                    "compressed payload requires the `compression` feature".to_string(),
+                ));
+            }
+        } else if self.payload.len() > max_len {
+            return Err(TransferError::SerializationError(
+                codec::DecodeError::TooLarge { size: self.payload.len(), max: max_len }.to_string(),
+            ));
+        } else {
+            self.payload.clone()
+        };
+
+        if codec::checksum(&json) != self.checksum {
+            return Err(TransferError::ChecksumMismatch);
+        }
+        serde_json::from_slice(&json).map_err(|e| TransferError::SerializationError(e.to_string()))
+    }
+}
+
 /// Request to initiate a player transfer.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransferRequest {
@@ -181,18 +352,18 @@ pub struct TransferResult {
     pub token: Option<TransferToken>,
     /// Error if failed
     pub error: Option<TransferError>,
-    /// Time taken for transfer in milliseconds
-    pub duration_ms: u64,
+    /// Time taken for transfer
+    pub duration_ms: Millis,
 }
 
 impl TransferResult {
     /// Creates a successful transfer result.
-    pub fn success(token: TransferToken, duration_ms: u64) -> Self {
+    pub fn success(token: TransferToken, duration_ms: impl Into<Millis>) -> Self {
         Self {
             success: true,
             token: Some(token),
             error: None,
-            duration_ms,
+            duration_ms: duration_ms.into(),
         }
     }
 
@@ -202,7 +373,18 @@ impl TransferResult {
             success: false,
             token: None,
             error: Some(error),
-            duration_ms: 0,
+            duration_ms: Millis(0),
+        }
+    }
+
+    /// Gets how long to wait before retrying this transfer.
+    ///
+    /// Returns None if the transfer succeeded or failed in a way a retry cannot
+    /// fix; otherwise advances `backoff` and returns its next delay.
+    pub fn retry_delay(&self, backoff: &mut Backoff) -> Option<Duration> {
+        match &self.error {
+            Some(error) if !self.success && error.is_retryable() => Some(backoff.next()),
+            _ => None,
         }
     }
 }
@@ -245,6 +427,467 @@ pub enum TransferError {
     /// Timeout waiting for transfer
     #[error("Transfer timeout")]
     Timeout,
+
+    /// Transfer payload uses an unsupported format version
+    #[error("Unsupported transfer format version: {0}")]
+    UnsupportedVersion(u16),
+
+    /// Transfer payload failed checksum verification
+    #[error("Transfer payload checksum mismatch")]
+    ChecksumMismatch,
+
+    /// State machine was asked to make an illegal transition
+    #[error("Invalid state transition from {from} to {to}")]
+    InvalidStateTransition { from: String, to: String },
+}
+
+impl TransferError {
+    /// Returns true for transient failures that may succeed on retry.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::TargetServerUnavailable(_)
+                | Self::TransferInProgress
+                | Self::NetworkError(_)
+                | Self::Timeout
+                | Self::ChecksumMismatch
+        )
+    }
+
+    /// Returns true if a different target server might accept the transfer.
+    ///
+    /// Covers capacity and version rejections as well as an unreachable
+    /// target; errors about the player or token itself are not.
+    pub fn try_alternate_target(&self) -> bool {
+        matches!(
+            self,
+            Self::TargetServerUnavailable(_)
+                | Self::TransferRejected(_)
+                | Self::UnsupportedVersion(_)
+                | Self::NetworkError(_)
+                | Self::Timeout
+        )
+    }
+}
+
+/// Phase of a single player transfer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferPhase {
+    /// Source server asked Atlas to transfer the player
+    #[default]
+    Requested,
+    /// Atlas issued a token and told the source to initiate
+    Initiated,
+    /// Target server accepted the incoming player
+    Accepted,
+    /// Player state was delivered to the target
+    StateTransferred,
+    /// Player connected to the target; transfer is done
+    Completed,
+    /// Player was returned to the source server
+    RolledBack,
+    /// Transfer was cancelled before any state moved
+    Cancelled,
+}
+
+/// Tracks one player transfer through its phases.
+///
+/// The source server stays authoritative for the player until the transfer
+/// completes, which is what makes [`TransferSession::rollback`] possible.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferSession {
+    /// Token identifying the transfer
+    pub token_id: String,
+    /// Player being transferred
+    pub player_id: PlayerId,
+    /// Source server
+    pub source_server: ServerId,
+    /// Target server
+    pub target_server: ServerId,
+    /// Current phase
+    pub phase: TransferPhase,
+}
+
+impl TransferSession {
+    /// Starts tracking the transfer authorized by `token`.
+    pub fn new(token: &TransferToken) -> Self {
+        Self {
+            token_id: token.token_id.clone(),
+            player_id: token.player_id.clone(),
+            source_server: token.source_server.clone(),
+            target_server: token.target_server.clone(),
+            phase: TransferPhase::Requested,
+        }
+    }
+
+    /// Atlas has initiated the transfer.
+    pub fn initiate(&mut self) -> Result<(), TransferError> {
+        self.apply(TransferEvent::Initiate)
+    }
+
+    /// The target server has accepted the player.
+    pub fn accept(&mut self) -> Result<(), TransferError> {
+        self.apply(TransferEvent::Accept)
+    }
+
+    /// The player state has been delivered to the target.
+    pub fn state_transferred(&mut self) -> Result<(), TransferError> {
+        self.apply(TransferEvent::StateTransferred)
+    }
+
+    /// The player has connected to the target.
+    pub fn complete(&mut self) -> Result<(), TransferError> {
+        self.apply(TransferEvent::Complete)
+    }
+
+    /// Cancels the transfer before the target has accepted it.
+    pub fn cancel(&mut self) -> Result<(), TransferError> {
+        self.apply(TransferEvent::Cancel)
+    }
+
+    /// Returns the player to the source server.
+    ///
+    /// Rollback is safe after the target accepted or received the state but
+    /// before the player connected to it: the source still holds the player's
+    /// authoritative state and can simply resume simulating them. Once the
+    /// transfer is `Completed` the source has released that state, so a player
+    /// who drops afterwards is lost to this transfer and must go through the
+    /// normal reconnect path instead.
+    pub fn rollback(&mut self) -> Result<(), TransferError> {
+        self.apply(TransferEvent::Rollback)
+    }
+
+    /// Applies a transfer event, advancing the phase if the event is legal.
+    ///
+    /// `TransferEvent::Request` is never legal here: it is what creates the session.
+    pub fn apply(&mut self, event: TransferEvent) -> Result<(), TransferError> {
+        if !event.is_allowed_in(self.phase) {
+            return Err(TransferError::InvalidStateTransition {
+                from: format!("{:?}", self.phase),
+                to: format!("{:?}", event.target_phase()),
+            });
+        }
+        self.phase = event.target_phase();
+        Ok(())
+    }
+
+    /// Server currently authoritative for the player.
+    pub fn authoritative_server(&self) -> &ServerId {
+        if self.phase == TransferPhase::Completed {
+            &self.target_server
+        } else {
+            &self.source_server
+        }
+    }
+
+    /// Returns true if the transfer has finished in any way.
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self.phase,
+            TransferPhase::Completed | TransferPhase::RolledBack | TransferPhase::Cancelled
+        )
+    }
+}
+
+/// A step in the player transfer protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferEvent {
+    /// Source server requested the transfer
+    Request,
+    /// Atlas initiated the transfer
+    Initiate,
+    /// Target server accepted the player
+    Accept,
+    /// Player state was delivered to the target
+    StateTransferred,
+    /// Player connected to the target
+    Complete,
+    /// Transfer was cancelled
+    Cancel,
+    /// Player was returned to the source server
+    Rollback,
+}
+
+impl TransferEvent {
+    /// Phase the transfer is in after this event.
+    pub fn target_phase(self) -> TransferPhase {
+        match self {
+            Self::Request => TransferPhase::Requested,
+            Self::Initiate => TransferPhase::Initiated,
+            Self::Accept => TransferPhase::Accepted,
+            Self::StateTransferred => TransferPhase::StateTransferred,
+            Self::Complete => TransferPhase::Completed,
+            Self::Cancel => TransferPhase::Cancelled,
+            Self::Rollback => TransferPhase::RolledBack,
+        }
+    }
+
+    /// Returns true if this event may follow a transfer in `phase`.
+    pub fn is_allowed_in(self, phase: TransferPhase) -> bool {
+        use TransferPhase as P;
+
+        match self {
+            Self::Request => false,
+            Self::Initiate => phase == P::Requested,
+            Self::Accept => phase == P::Initiated,
+            Self::StateTransferred => phase == P::Accepted,
+            Self::Complete => phase == P::StateTransferred,
+            Self::Cancel => matches!(phase, P::Requested | P::Initiated),
+            Self::Rollback => matches!(phase, P::Accepted | P::StateTransferred),
+        }
+    }
+}
+
+/// First protocol violation found by [`validate_handoff`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum HandoffViolation {
+    /// Sequence does not start with `TransferEvent::Request`
+    #[error("Handoff does not start with a transfer request")]
+    MissingRequest,
+
+    /// Event is not allowed in the phase the transfer was in
+    #[error("Unexpected {event:?} at index {index} in phase {phase:?}")]
+    UnexpectedEvent {
+        index: usize,
+        event: TransferEvent,
+        phase: TransferPhase,
+    },
+
+    /// Sequence ended before the transfer finished
+    #[error("Handoff ended in non-terminal phase {0:?}")]
+    Incomplete(TransferPhase),
+}
+
+/// Checks that a sequence of events forms one legal, finished transfer.
+///
+/// The sequence must start with `Request`, follow the [`TransferSession`]
+/// transition rules, and end in a terminal phase with nothing after it.
+/// Intended for tests and for debugging captured message logs.
+pub fn validate_handoff(events: &[TransferEvent]) -> Result<(), HandoffViolation> {
+    let Some((TransferEvent::Request, rest)) = events.split_first() else {
+        return Err(HandoffViolation::MissingRequest);
+    };
+
+    let mut phase = TransferPhase::Requested;
+    for (offset, &event) in rest.iter().enumerate() {
+        if !event.is_allowed_in(phase) {
+            return Err(HandoffViolation::UnexpectedEvent {
+                index: offset + 1,
+                event,
+                phase,
+            });
+        }
+        phase = event.target_phase();
+    }
+
+    match phase {
+        TransferPhase::Completed | TransferPhase::RolledBack | TransferPhase::Cancelled => Ok(()),
+        _ => Err(HandoffViolation::Incomplete(phase)),
+    }
+}
+
+/// State of a region ownership handoff.
+///
+/// A region handoff is distinct from a player transfer: a player transfer moves
+/// one player between two servers that both keep their regions, while a region
+/// handoff moves authority over an entire grid cell (and every player in it) to
+/// another server, typically during rebalancing. Atlas drives the handoff with
+/// `AtlasMessage::TransferRegionOwnership` and the current owner answers with
+/// `HorizonMessage::RegionOwnershipReleased` once it has stopped simulating the region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RegionHandoffState {
+    /// Atlas has asked the current owner to give up the region
+    #[default]
+    Requested,
+    /// Current owner is draining players and flushing region state
+    Releasing,
+    /// Current owner has released the region
+    Released,
+    /// New owner has taken over the region
+    Completed,
+    /// Handoff was abandoned and the region stays with the original owner
+    Aborted,
+}
+
+impl RegionHandoffState {
+    /// Moves to `Releasing` once the current owner starts draining.
+    pub fn begin_release(self) -> Result<Self, TransferError> {
+        match self {
+            Self::Requested => Ok(Self::Releasing),
+            _ => Err(self.invalid_transition(Self::Releasing)),
+        }
+    }
+
+    /// Moves to `Released` once the current owner reports the region released.
+    pub fn mark_released(self) -> Result<Self, TransferError> {
+        match self {
+            Self::Requested | Self::Releasing => Ok(Self::Released),
+            _ => Err(self.invalid_transition(Self::Released)),
+        }
+    }
+
+    /// Moves to `Completed` once the new owner has taken over the region.
+    pub fn complete(self) -> Result<Self, TransferError> {
+        match self {
+            Self::Released => Ok(Self::Completed),
+            _ => Err(self.invalid_transition(Self::Completed)),
+        }
+    }
+
+    /// Abandons the handoff.
+    ///
+    /// Only possible before the region is released; afterwards the original
+    /// owner no longer holds the region and the handoff must be completed.
+    pub fn abort(self) -> Result<Self, TransferError> {
+        match self {
+            Self::Requested | Self::Releasing => Ok(Self::Aborted),
+            _ => Err(self.invalid_transition(Self::Aborted)),
+        }
+    }
+
+    /// Returns true if the handoff has finished (completed or aborted).
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, Self::Completed | Self::Aborted)
+    }
+
+    fn invalid_transition(self, to: Self) -> TransferError {
+        TransferError::InvalidStateTransition {
+            from: format!("{:?}", self),
+            to: format!("{:?}", to),
+        }
+    }
+}
+
+/// Attempt and success counts for one region pair.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct PairStats {
+    attempts: u64,
+    successes: u64,
+}
+
+/// Transfer success rates per (source region, target region) pair.
+///
+/// Atlas records the outcome of every transfer so it can alert when transfers
+/// across a particular region border start failing. Direction matters: A to B
+/// and B to A are tracked separately.
+#[derive(Debug, Clone, Default)]
+pub struct TransferMetrics {
+    pairs: HashMap<(RegionCoordinate, RegionCoordinate), PairStats>,
+}
+
+impl TransferMetrics {
+    /// Creates an empty tracker.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the outcome of a transfer from one region to another.
+    pub fn record(&mut self, from: RegionCoordinate, to: RegionCoordinate, success: bool) {
+        let stats = self.pairs.entry((from, to)).or_default();
+        stats.attempts += 1;
+        if success {
+            stats.successes += 1;
+        }
+    }
+
+    /// Number of transfers recorded for a region pair.
+    pub fn attempts(&self, from: RegionCoordinate, to: RegionCoordinate) -> u64 {
+        self.pairs.get(&(from, to)).map_or(0, |s| s.attempts)
+    }
+
+    /// Fraction of successful transfers for a region pair (0.0 to 1.0).
+    ///
+    /// Returns None if no transfers have been recorded for the pair.
+    pub fn success_rate(&self, from: RegionCoordinate, to: RegionCoordinate) -> Option<f32> {
+        let stats = self.pairs.get(&(from, to))?;
+        Some(stats.successes as f32 / stats.attempts as f32)
+    }
+}
+
+/// Records how long transfers take, for latency SLA reporting.
+///
+/// Atlas calls [`TransferLatencyTracker::start`] when it issues a token and
+/// [`TransferLatencyTracker::complete`] when the player reaches the target.
+/// Timestamps are milliseconds since the Unix epoch. Only the most recent
+/// `capacity` latencies are kept, and transfers that never complete should be
+/// dropped with [`TransferLatencyTracker::expire_before`].
+#[derive(Debug, Clone)]
+pub struct TransferLatencyTracker {
+    capacity: usize,
+    started_at_ms: HashMap<String, Millis>,
+    latencies_ms: VecDeque<Millis>,
+}
+
+impl TransferLatencyTracker {
+    /// Creates an empty tracker keeping at most `capacity` latencies.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            started_at_ms: HashMap::new(),
+            latencies_ms: VecDeque::new(),
+        }
+    }
+
+    /// Maximum number of latencies kept.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Records the start of a transfer.
+    pub fn start(&mut self, token_id: impl Into<String>, at_ms: Millis) {
+        self.started_at_ms.insert(token_id.into(), at_ms);
+    }
+
+    /// Records the completion of a transfer, returning its latency.
+    ///
+    /// Returns None if the transfer was never started (or already completed
+    /// or expired).
+    pub fn complete(&mut self, token_id: &str, at_ms: Millis) -> Option<Millis> {
+        let started = self.started_at_ms.remove(token_id)?;
+        let latency = at_ms - started;
+        while !self.latencies_ms.is_empty() && self.latencies_ms.len() >= self.capacity {
+            self.latencies_ms.pop_front();
+        }
+        if self.capacity > 0 {
+            self.latencies_ms.push_back(latency);
+        }
+        Some(latency)
+    }
+
+    /// Forgets transfers started before `at_ms`, returning how many were dropped.
+    pub fn expire_before(&mut self, at_ms: Millis) -> usize {
+        let before = self.started_at_ms.len();
+        self.started_at_ms.retain(|_, started| *started >= at_ms);
+        before - self.started_at_ms.len()
+    }
+
+    /// Number of completed transfers in the window.
+    pub fn completed(&self) -> usize {
+        self.latencies_ms.len()
+    }
+
+    /// Number of transfers started but not yet completed.
+    pub fn in_flight(&self) -> usize {
+        self.started_at_ms.len()
+    }
+
+    /// 99th percentile latency of completed transfers in the window (nearest rank).
+    pub fn p99(&self) -> Option<Millis> {
+        if self.latencies_ms.is_empty() {
+            return None;
+        }
+        let mut sorted: Vec<Millis> = self.latencies_ms.iter().copied().collect();
+        let rank = (sorted.len() * 99).div_ceil(100);
+        Some(*sorted.select_nth_unstable(rank - 1).1)
+    }
+
+    /// Number of completed transfers in the window that took longer than `budget_ms`.
+    pub fn breaches(&self, budget_ms: Millis) -> usize {
+        self.latencies_ms.iter().filter(|&&l| l > budget_ms).count()
+    }
 }
 
 /// Transfer notification sent to clients.
@@ -256,14 +899,101 @@ pub struct TransferNotification {
     pub target_address: String,
     /// Transfer token to present
     pub token: String,
-    /// Suggested reconnect delay in milliseconds
-    pub reconnect_delay_ms: u64,
+    /// Suggested reconnect delay
+    pub reconnect_delay_ms: Millis,
+}
+
+/// How a player's move between regions relates to one observed region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CrossingKind {
+    /// Player moved into the region
+    Entered,
+    /// Player moved out of the region
+    Exited,
+    /// Player cut diagonally across an edge or corner of the region without
+    /// stopping in it
+    Grazed,
+}
+
+/// A player crossing between regions, emitted by Atlas.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BoundaryCrossing {
+    /// Player that crossed
+    pub player_id: PlayerId,
+    /// Region the player left
+    pub from_region: RegionCoordinate,
+    /// Region the player arrived in
+    pub to_region: RegionCoordinate,
+    /// World position where the crossing was detected
+    pub at: WorldCoordinate,
+    /// When the crossing was detected (Unix ms)
+    pub timestamp_ms: u64,
+    /// Crossing relative to the region reporting it
+    pub kind: CrossingKind,
+}
+
+/// Classifies a move from `prev` to `curr` from the point of view of `region`.
+///
+/// Returns None if the player stayed in one region or the move did not touch
+/// `region`. A move is grazing when it changes more than one axis at once and
+/// `region` lies in the block spanned by `prev` and `curr`.
+pub fn classify_crossing(
+    region: &RegionCoordinate,
+    prev: &RegionCoordinate,
+    curr: &RegionCoordinate,
+) -> Option<CrossingKind> {
+    if prev == curr {
+        return None;
+    }
+    if curr == region {
+        return Some(CrossingKind::Entered);
+    }
+    if prev == region {
+        return Some(CrossingKind::Exited);
+    }
+    let diagonal = prev.manhattan_distance(curr) > prev.chebyshev_distance(curr);
+    let spans = |a: i64, b: i64, v: i64| (a.min(b)..=a.max(b)).contains(&v);
+    let touched = spans(prev.x, curr.x, region.x) && spans(prev.y, curr.y, region.y) && spans(prev.z, curr.z, region.z);
+    (diagonal && touched).then_some(CrossingKind::Grazed)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_classify_crossing() {
+        let region = RegionCoordinate::new(1, 0, 0);
+        let origin = RegionCoordinate::center();
+        assert_eq!(classify_crossing(&region, &origin, &region), Some(CrossingKind::Entered));
+        assert_eq!(classify_crossing(&region, &region, &origin), Some(CrossingKind::Exited));
+        assert_eq!(classify_crossing(&region, &region, &region), None);
+        // Diagonal step from (0,0,0) to (1,1,0) passes the corner of (1,0,0).
+        assert_eq!(
+            classify_crossing(&region, &origin, &RegionCoordinate::new(1, 1, 0)),
+            Some(CrossingKind::Grazed)
+        );
+        assert_eq!(classify_crossing(&region, &origin, &RegionCoordinate::new(0, 1, 0)), None);
+        assert_eq!(classify_crossing(&region, &origin, &RegionCoordinate::new(-1, -1, 0)), None);
+    }
+
+    #[test]
+    fn test_boundary_crossing_serialization() {
+        let crossing = BoundaryCrossing {
+            player_id: PlayerId::from("p1"),
+            from_region: RegionCoordinate::center(),
+            to_region: RegionCoordinate::new(1, 0, 0),
+            at: WorldCoordinate::new(500.0, 12.0, 0.0),
+            timestamp_ms: 1_700_000_000_000,
+            kind: CrossingKind::Entered,
+        };
+        let json = serde_json::to_value(&crossing).unwrap();
+        assert_eq!(json["kind"], "entered");
+        let restored: BoundaryCrossing = serde_json::from_value(json).unwrap();
+        assert_eq!(restored, crossing);
+    }
+
     #[test]
     fn test_transfer_token_creation_and_verification() {
         let player_id = PlayerId::new();
@@ -299,4 +1029,229 @@ mod tests {
             Err(TransferError::InvalidSignature)
         ));
     }
+
+    #[test]
+    fn test_batch_transfer_token() {
+        let players = vec![PlayerId::from("p1"), PlayerId::from("p2")];
+        let mut token = BatchTransferToken::new(
+            ServerId::from("horizon-1"),
+            ServerId::from("horizon-2"),
+            players,
+            b"secret",
+        );
+        assert!(token.contains(&PlayerId::from("p2")));
+        assert!(!token.contains(&PlayerId::from("p3")));
+        assert!(!token.is_expired());
+
+        let json = serde_json::to_string(&token).unwrap();
+        let restored: BatchTransferToken = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.player_ids.len(), 2);
+        assert!(restored.verify(b"secret").is_ok());
+
+        token.expires_at_ms = 0;
+        assert!(token.is_expired());
+        assert!(matches!(token.verify(b"secret"), Err(TransferError::TokenExpired)));
+    }
+
+    fn sample_state() -> PlayerState {
+        let info = crate::player::PlayerInfo::new(PlayerId::from("p1"), "TestPlayer".to_string());
+        let mut state = PlayerState::new(info);
+        state.persistent_data = serde_json::json!({"inventory": vec!["sword"; 50]});
+        state
+    }
+
+    #[test]
+    fn test_transfer_envelope_uncompressed_round_trip() {
+        let envelope = TransferEnvelope::encode(&sample_state(), false).unwrap();
+        assert!(!envelope.compressed);
+        assert_eq!(envelope.version, TRANSFER_ENVELOPE_VERSION);
+
+        let json = serde_json::to_value(&envelope).unwrap();
+        assert!(json["payload"].is_string());
+        let restored: TransferEnvelope = serde_json::from_value(json).unwrap();
+        assert_eq!(restored.decode().unwrap().info.name, "TestPlayer");
+        assert!(matches!(restored.decode_limited(16), Err(TransferError::SerializationError(_))));
+
+        let mut corrupted = envelope.clone();
+        corrupted.checksum ^= 1;
+        assert!(matches!(corrupted.decode(), Err(TransferError::ChecksumMismatch)));
+
+        let mut future = envelope;
+        future.version = TRANSFER_ENVELOPE_VERSION + 1;
+        assert!(matches!(future.decode(), Err(TransferError::UnsupportedVersion(_))));
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn test_transfer_envelope_compressed_round_trip() {
+        let state = sample_state();
+        let plain = TransferEnvelope::encode(&state, false).unwrap();
+        let envelope = TransferEnvelope::encode(&state, true).unwrap();
+        assert!(envelope.compressed);
+        assert!(envelope.payload.len() < plain.payload.len());
+        assert_eq!(envelope.checksum, plain.checksum);
+        assert_eq!(envelope.decode().unwrap().persistent_data, state.persistent_data);
+        assert!(matches!(
+            envelope.decode_limited(plain.payload.len() - 1),
+            Err(TransferError::SerializationError(_))
+        ));
+    }
+
+    #[test]
+    fn test_transfer_session_rollback() {
+        let token = TransferToken::new(
+            PlayerId::from("p1"),
+            ServerId::from("source"),
+            ServerId::from("target"),
+            "127.0.0.1:8081".to_string(),
+            b"secret",
+        );
+        let mut session = TransferSession::new(&token);
+        session.initiate().unwrap();
+        session.accept().unwrap();
+        session.state_transferred().unwrap();
+        session.rollback().unwrap();
+        assert_eq!(session.phase, TransferPhase::RolledBack);
+        assert_eq!(session.authoritative_server().as_str(), "source");
+        assert!(session.is_terminal());
+
+        let mut completed = TransferSession::new(&token);
+        completed.initiate().unwrap();
+        completed.accept().unwrap();
+        completed.state_transferred().unwrap();
+        completed.complete().unwrap();
+        assert_eq!(completed.authoritative_server().as_str(), "target");
+        assert!(matches!(
+            completed.rollback(),
+            Err(TransferError::InvalidStateTransition { .. })
+        ));
+        assert!(TransferSession::new(&token).rollback().is_err());
+    }
+
+    #[test]
+    fn test_validate_handoff() {
+        use TransferEvent::*;
+
+        assert_eq!(validate_handoff(&[Request, Initiate, Accept, StateTransferred, Complete]), Ok(()));
+        assert_eq!(validate_handoff(&[Request, Initiate, Accept, Rollback]), Ok(()));
+        assert_eq!(validate_handoff(&[Request, Cancel]), Ok(()));
+
+        assert_eq!(
+            validate_handoff(&[Request, Initiate, StateTransferred, Complete]),
+            Err(HandoffViolation::UnexpectedEvent {
+                index: 2,
+                event: StateTransferred,
+                phase: TransferPhase::Initiated,
+            })
+        );
+        assert_eq!(
+            validate_handoff(&[Request, Initiate, Accept, StateTransferred, Complete, Complete]),
+            Err(HandoffViolation::UnexpectedEvent {
+                index: 5,
+                event: Complete,
+                phase: TransferPhase::Completed,
+            })
+        );
+        assert_eq!(
+            validate_handoff(&[Request, Initiate, Accept]),
+            Err(HandoffViolation::Incomplete(TransferPhase::Accepted))
+        );
+        assert_eq!(validate_handoff(&[Initiate, Accept]), Err(HandoffViolation::MissingRequest));
+        assert_eq!(validate_handoff(&[]), Err(HandoffViolation::MissingRequest));
+    }
+
+    #[test]
+    fn test_transfer_notification_wire_format() {
+        let json = r#"{"player_id":"p1","target_address":"127.0.0.1:8081","token":"t","reconnect_delay_ms":250}"#;
+        let notification: TransferNotification = serde_json::from_str(json).unwrap();
+        assert_eq!(notification.reconnect_delay_ms.as_duration(), std::time::Duration::from_millis(250));
+        assert_eq!(serde_json::to_string(&notification).unwrap(), json);
+    }
+
+    #[test]
+    fn test_transfer_result_retry_delay() {
+        let mut backoff = Backoff::new(Duration::from_millis(100), Duration::from_secs(5), 2.0);
+        let unavailable = TransferResult::failure(TransferError::TargetServerUnavailable("horizon-2".to_string()));
+        assert_eq!(unavailable.retry_delay(&mut backoff), Some(Duration::from_millis(100)));
+        assert_eq!(unavailable.retry_delay(&mut backoff), Some(Duration::from_millis(200)));
+
+        let rejected = TransferResult::failure(TransferError::InvalidSignature);
+        assert_eq!(rejected.retry_delay(&mut backoff), None);
+        assert_eq!(backoff.attempts(), 2);
+    }
+
+    #[test]
+    fn test_transfer_error_alternate_target() {
+        assert!(TransferError::TransferRejected("server full".to_string()).try_alternate_target());
+        assert!(TransferError::UnsupportedVersion(9).try_alternate_target());
+        assert!(!TransferError::TokenExpired.try_alternate_target());
+        assert!(!TransferError::PlayerNotFound("p1".to_string()).try_alternate_target());
+    }
+
+    #[test]
+    fn test_transfer_latency_tracker() {
+        let mut tracker = TransferLatencyTracker::new(5);
+        assert_eq!(tracker.p99(), None);
+
+        for (i, latency) in [120u64, 80, 450, 95, 300].into_iter().enumerate() {
+            let token = format!("token-{}", i);
+            tracker.start(token.clone(), Millis(1_000));
+            assert_eq!(tracker.complete(&token, Millis(1_000 + latency)), Some(Millis(latency)));
+        }
+        tracker.start("pending", Millis(2_000));
+        assert_eq!(tracker.complete("unknown", Millis(3_000)), None);
+
+        assert_eq!(tracker.completed(), 5);
+        assert_eq!(tracker.in_flight(), 1);
+        assert_eq!(tracker.breaches(Millis(250)), 2);
+        assert_eq!(tracker.breaches(Millis(500)), 0);
+        assert_eq!(tracker.p99(), Some(Millis(450)));
+
+        // The window drops the oldest latency (120 ms) once full.
+        tracker.start("late", Millis(4_000));
+        tracker.complete("late", Millis(4_010));
+        assert_eq!(tracker.completed(), 5);
+        assert_eq!(tracker.breaches(Millis(100)), 2);
+
+        tracker.start("stuck", Millis(9_000));
+        assert_eq!(tracker.expire_before(Millis(5_000)), 1);
+        assert_eq!(tracker.complete("pending", Millis(6_000)), None);
+        assert_eq!(tracker.in_flight(), 1);
+    }
+
+    #[test]
+    fn test_transfer_metrics_success_rate() {
+        let a = RegionCoordinate::new(0, 0, 0);
+        let b = RegionCoordinate::new(1, 0, 0);
+        let mut metrics = TransferMetrics::new();
+        assert_eq!(metrics.success_rate(a, b), None);
+
+        for success in [true, true, false, true] {
+            metrics.record(a, b, success);
+        }
+        metrics.record(b, a, false);
+
+        assert_eq!(metrics.attempts(a, b), 4);
+        assert!((metrics.success_rate(a, b).unwrap() - 0.75).abs() < 0.001);
+        assert_eq!(metrics.success_rate(b, a), Some(0.0));
+        assert_eq!(metrics.success_rate(a, RegionCoordinate::new(0, 1, 0)), None);
+    }
+
+    #[test]
+    fn test_region_handoff_state_machine() {
+        let state = RegionHandoffState::Requested
+            .begin_release()
+            .and_then(RegionHandoffState::mark_released)
+            .and_then(RegionHandoffState::complete)
+            .unwrap();
+        assert_eq!(state, RegionHandoffState::Completed);
+        assert!(state.is_terminal());
+
+        assert_eq!(RegionHandoffState::Releasing.abort().unwrap(), RegionHandoffState::Aborted);
+        assert!(matches!(
+            RegionHandoffState::Released.abort(),
+            Err(TransferError::InvalidStateTransition { .. })
+        ));
+        assert!(RegionHandoffState::Requested.complete().is_err());
+    }
 }
@@ -0,0 +1,174 @@
+//! Typed time units for wire fields.
+//!
+//! `Seconds` and `Millis` serialize as plain integers, so replacing a bare
+//! `*_secs: u32` or `*_ms: u64` field with them does not change the wire format.
+
+use serde::{Deserialize, Serialize};
+use std::ops::{Add, AddAssign, Sub};
+use std::time::Duration;
+
+/// A whole number of seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Default)]
+#[serde(transparent)]
+pub struct Seconds(pub u32);
+
+impl Seconds {
+    /// Creates a value from a number of seconds.
+    pub const fn new(secs: u32) -> Self {
+        Self(secs)
+    }
+
+    /// Gets the number of seconds.
+    pub const fn get(self) -> u32 {
+        self.0
+    }
+
+    /// Converts to a `std::time::Duration`.
+    pub fn as_duration(self) -> Duration {
+        Duration::from_secs(self.0 as u64)
+    }
+
+    /// Converts to milliseconds.
+    pub fn as_millis(self) -> Millis {
+        Millis(self.0 as u64 * 1000)
+    }
+}
+
+/// A whole number of milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, Default)]
+#[serde(transparent)]
+pub struct Millis(pub u64);
+
+impl Millis {
+    /// Creates a value from a number of milliseconds.
+    pub const fn new(ms: u64) -> Self {
+        Self(ms)
+    }
+
+    /// Gets the number of milliseconds.
+    pub const fn get(self) -> u64 {
+        self.0
+    }
+
+    /// Converts to a `std::time::Duration`.
+    pub fn as_duration(self) -> Duration {
+        Duration::from_millis(self.0)
+    }
+
+    /// Converts a duration, saturating at `u64::MAX` milliseconds.
+    pub fn from_duration(duration: Duration) -> Self {
+        Self(duration.as_millis().min(u64::MAX as u128) as u64)
+    }
+}
+
+impl From<u32> for Seconds {
+    fn from(secs: u32) -> Self {
+        Self(secs)
+    }
+}
+
+impl From<u64> for Millis {
+    fn from(ms: u64) -> Self {
+        Self(ms)
+    }
+}
+
+impl From<Seconds> for Millis {
+    fn from(secs: Seconds) -> Self {
+        secs.as_millis()
+    }
+}
+
+impl From<Seconds> for Duration {
+    fn from(secs: Seconds) -> Self {
+        secs.as_duration()
+    }
+}
+
+impl From<Millis> for Duration {
+    fn from(ms: Millis) -> Self {
+        ms.as_duration()
+    }
+}
+
+impl Add for Seconds {
+    type Output = Seconds;
+
+    /// Saturates at the maximum.
+    fn add(self, rhs: Seconds) -> Seconds {
+        Seconds(self.0.saturating_add(rhs.0))
+    }
+}
+
+impl AddAssign for Seconds {
+    /// Saturates at the maximum.
+    fn add_assign(&mut self, rhs: Seconds) {
+        self.0 = self.0.saturating_add(rhs.0);
+    }
+}
+
+impl Sub for Seconds {
+    type Output = Seconds;
+
+    /// Saturates at zero.
+    fn sub(self, rhs: Seconds) -> Seconds {
+        Seconds(self.0.saturating_sub(rhs.0))
+    }
+}
+
+impl Add for Millis {
+    type Output = Millis;
+
+    /// Saturates at the maximum.
+    fn add(self, rhs: Millis) -> Millis {
+        Millis(self.0.saturating_add(rhs.0))
+    }
+}
+
+impl AddAssign for Millis {
+    /// Saturates at the maximum.
+    fn add_assign(&mut self, rhs: Millis) {
+        self.0 = self.0.saturating_add(rhs.0);
+    }
+}
+
+impl Sub for Millis {
+    type Output = Millis;
+
+    /// Saturates at zero.
+    fn sub(self, rhs: Millis) -> Millis {
+        Millis(self.0.saturating_sub(rhs.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_units_serialize_as_integers() {
+        assert_eq!(serde_json::to_string(&Seconds(30)).unwrap(), "30");
+        assert_eq!(serde_json::to_string(&Millis(1500)).unwrap(), "1500");
+        assert_eq!(serde_json::from_str::<Seconds>("30").unwrap(), Seconds(30));
+        assert_eq!(serde_json::from_str::<Millis>("1500").unwrap(), Millis(1500));
+    }
+
+    #[test]
+    fn test_unit_conversions_and_arithmetic() {
+        assert_eq!(Seconds(3).as_duration(), Duration::from_secs(3));
+        assert_eq!(Millis::from(Seconds(3)), Millis(3000));
+        assert_eq!(Millis(250).as_duration(), Duration::from_millis(250));
+        assert_eq!(Millis::from_duration(Duration::from_micros(2_500)), Millis(2));
+
+        let mut total = Millis(100) + Seconds(1).as_millis();
+        total += Millis(50);
+        assert_eq!(total, Millis(1150));
+        assert_eq!(Millis(10) - Millis(20), Millis(0));
+        assert_eq!(Seconds(5) - Seconds(2), Seconds(3));
+
+        let mut max = Millis(u64::MAX);
+        max += Millis(1);
+        assert_eq!(max, Millis(u64::MAX));
+        assert_eq!(Seconds(u32::MAX) + Seconds(1), Seconds(u32::MAX));
+    }
+}
@@ -0,0 +1,122 @@
+//! Small utilities shared across the crate.
+
+use std::time::Duration;
+
+/// Exponential backoff for retries.
+///
+/// Each call to [`Backoff::next`] returns `base * multiplier^attempt`, capped
+/// at `max`. With jitter enabled the delay is drawn uniformly from the upper
+/// half of that range, which spreads out retries from many clients that failed
+/// at the same moment.
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    /// Delay before the first retry
+    pub base: Duration,
+    /// Upper bound on any delay
+    pub max: Duration,
+    /// Growth factor between attempts
+    pub multiplier: f64,
+    /// Whether to randomize delays
+    pub jitter: bool,
+    attempt: u32,
+}
+
+impl Backoff {
+    /// Creates a backoff without jitter.
+    pub fn new(base: Duration, max: Duration, multiplier: f64) -> Self {
+        Self {
+            base,
+            max,
+            multiplier,
+            jitter: false,
+            attempt: 0,
+        }
+    }
+
+    /// Enables jitter.
+    pub fn with_jitter(mut self) -> Self {
+        self.jitter = true;
+        self
+    }
+
+    /// Returns the delay before the next retry and advances the attempt count.
+    // Not an `Iterator`: a backoff never runs out, so `Option` would only add noise.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Duration {
+        let scale = self.multiplier.max(1.0).powi(self.attempt.min(i32::MAX as u32) as i32);
+        let delay = self.max.as_secs_f64().min(self.base.as_secs_f64() * scale);
+        self.attempt = self.attempt.saturating_add(1);
+
+        let delay = if self.jitter {
+            delay * (0.5 + 0.5 * random_unit())
+        } else {
+            delay
+        };
+        // Rounding can push a delay near `Duration::MAX` out of range.
+        Duration::try_from_secs_f64(delay).map_or(self.max, |delay| delay.min(self.max))
+    }
+
+    /// Number of delays handed out since creation or the last reset.
+    pub fn attempts(&self) -> u32 {
+        self.attempt
+    }
+
+    /// Starts over from the base delay, e.g. after a successful attempt.
+    pub fn reset(&mut self) {
+        self.attempt = 0;
+    }
+}
+
+/// Random value in `[0, 1)` drawn from the random bits of a v4 UUID.
+///
+/// A v4 UUID fixes the version nibble (bits 76..80) and the variant bits
+/// (bits 62..64), so the 53 bits used come from the 48 bits above the version
+/// and the 5 bits just below the variant.
+fn random_unit() -> f64 {
+    let uuid = uuid::Uuid::new_v4().as_u128();
+    let bits = ((uuid >> 80) as u64) << 5 | ((uuid >> 57) as u64 & 0x1f);
+    bits as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_growth_and_cap() {
+        let mut backoff = Backoff::new(Duration::from_millis(100), Duration::from_secs(1), 2.0);
+        let delays: Vec<u128> = (0..6).map(|_| backoff.next().as_millis()).collect();
+        assert_eq!(delays, vec![100, 200, 400, 800, 1000, 1000]);
+        assert_eq!(backoff.attempts(), 6);
+
+        backoff.reset();
+        assert_eq!(backoff.next(), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_backoff_jitter_bounds() {
+        let mut backoff = Backoff::new(Duration::from_millis(400), Duration::from_millis(400), 2.0).with_jitter();
+        let delays: Vec<Duration> = (0..100).map(|_| backoff.next()).collect();
+        for delay in &delays {
+            assert!(*delay >= Duration::from_millis(200) && *delay <= Duration::from_millis(400));
+        }
+        assert!(delays.iter().any(|d| *d < Duration::from_millis(250)));
+        assert!(delays.iter().any(|d| *d > Duration::from_millis(350)));
+    }
+
+    #[test]
+    fn test_random_unit_range() {
+        let samples: Vec<f64> = (0..1000).map(|_| random_unit()).collect();
+        assert!(samples.iter().all(|v| (0.0..1.0).contains(v)));
+        assert!(samples.iter().any(|v| *v < 0.1));
+        assert!(samples.iter().any(|v| *v > 0.9));
+    }
+
+    #[test]
+    fn test_backoff_near_duration_max() {
+        let mut backoff = Backoff::new(Duration::MAX, Duration::MAX, 2.0).with_jitter();
+        assert!(backoff.next() <= Duration::MAX);
+        let mut backoff = Backoff::new(Duration::MAX, Duration::MAX, 2.0);
+        assert_eq!(backoff.next(), Duration::MAX);
+    }
+}